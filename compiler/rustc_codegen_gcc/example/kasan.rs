@@ -0,0 +1,20 @@
+#![feature(no_core, lang_items)]
+#![no_core]
+
+#[lang = "sized"]
+pub trait Sized {}
+
+#[lang = "copy"]
+pub trait Copy {}
+
+impl Copy for u32 {}
+
+#[no_mangle]
+pub unsafe fn kasan_load(ptr: *const u32) -> u32 {
+    *ptr
+}
+
+#[no_mangle]
+pub unsafe fn kasan_store(ptr: *mut u32, value: u32) {
+    *ptr = value;
+}
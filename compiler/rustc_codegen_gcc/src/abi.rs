@@ -3,9 +3,14 @@
 use rustc_data_structures::fx::FxHashSet;
 use rustc_middle::bug;
 use rustc_middle::ty::Ty;
-use rustc_target::abi::call::{CastTarget, FnAbi, PassMode, Reg, RegKind};
+use rustc_middle::ty::layout::LayoutOf;
+use rustc_target::abi::call::{ArgAttributes, CastTarget, FnAbi, PassMode, Reg, RegKind};
+#[cfg(feature="master")]
+use rustc_target::abi::call::ArgAttribute;
 
 use crate::builder::Builder;
+#[cfg(feature="master")]
+use crate::common::type_is_pointer;
 use crate::context::CodegenCx;
 use crate::intrinsic::ArgAbiExt;
 use crate::type_of::LayoutGccExt;
@@ -51,9 +56,12 @@ fn gcc_type<'gcc>(&self, cx: &CodegenCx<'gcc, '_>) -> Type<'gcc> {
                 return rest_gcc_unit;
             }
 
-            // Simplify to array when all chunks are the same size and type
+            // NOTE: unlike LLVM, GCC does not pass or return arrays in registers, so a struct is
+            // used even when all chunks are the same size and type. This way, values like
+            // `(u64, u64)` are returned in rax:rdx or x0:x1 instead of through a hidden pointer.
             if rem_bytes == 0 {
-                return cx.type_array(rest_gcc_unit, rest_count);
+                let fields: Vec<_> = (0..rest_count).map(|_| rest_gcc_unit).collect();
+                return cx.type_struct(&fields, false);
             }
         }
 
@@ -93,7 +101,7 @@ fn gcc_type<'gcc>(&self, cx: &CodegenCx<'gcc, '_>) -> Type<'gcc> {
                     _ => bug!("unsupported float: {:?}", self),
                 }
             },
-            RegKind::Vector => unimplemented!(), //cx.type_vector(cx.type_i8(), self.size.bytes()),
+            RegKind::Vector => cx.type_vector(cx.type_i8(), self.size.bytes()),
         }
     }
 }
@@ -124,17 +132,38 @@ fn gcc_type(&self, cx: &CodegenCx<'gcc, 'tcx>) -> (Type<'gcc>, Vec<Type<'gcc>>,
                 }
             };
 
+        #[cfg(feature="master")]
+        let apply_attrs = |ty: Type<'gcc>, attrs: &ArgAttributes| {
+            // NOTE: restrict is the GCC equivalent of the noalias attribute of LLVM on parameters.
+            if attrs.regular.contains(ArgAttribute::NoAlias) && type_is_pointer(ty) {
+                ty.make_restrict()
+            }
+            else {
+                ty
+            }
+        };
+        #[cfg(not(feature="master"))]
+        let apply_attrs = |ty: Type<'gcc>, _attrs: &ArgAttributes| {
+            ty
+        };
+
         for arg in self.args.iter() {
             let arg_ty = match arg.mode {
                 PassMode::Ignore => continue,
-                PassMode::Direct(_) => arg.layout.immediate_gcc_type(cx),
-                PassMode::Pair(..) => {
-                    argument_tys.push(arg.layout.scalar_pair_element_gcc_type(cx, 0, true));
-                    argument_tys.push(arg.layout.scalar_pair_element_gcc_type(cx, 1, true));
+                PassMode::Direct(ref attrs) => apply_attrs(arg.layout.immediate_gcc_type(cx), attrs),
+                PassMode::Pair(ref a, ref b) => {
+                    argument_tys.push(apply_attrs(arg.layout.scalar_pair_element_gcc_type(cx, 0, true), a));
+                    argument_tys.push(apply_attrs(arg.layout.scalar_pair_element_gcc_type(cx, 1, true), b));
                     continue;
                 }
                 PassMode::Indirect { extra_attrs: Some(_), .. } => {
-                    unimplemented!();
+                    // NOTE: unsized arguments are passed as a pointer to the data followed by the
+                    // metadata, like a fat pointer.
+                    let ptr_ty = cx.tcx.mk_mut_ptr(arg.layout.ty);
+                    let ptr_layout = cx.layout_of(ptr_ty);
+                    argument_tys.push(ptr_layout.scalar_pair_element_gcc_type(cx, 0, true));
+                    argument_tys.push(ptr_layout.scalar_pair_element_gcc_type(cx, 1, true));
+                    continue;
                 }
                 PassMode::Cast(ref cast, pad_i32) => {
                     // add padding
@@ -143,11 +172,17 @@ fn gcc_type(&self, cx: &CodegenCx<'gcc, 'tcx>) -> (Type<'gcc>, Vec<Type<'gcc>>,
                     }
                     cast.gcc_type(cx)
                 }
-                PassMode::Indirect { extra_attrs: None, on_stack: true, .. } => {
+                PassMode::Indirect { ref attrs, extra_attrs: None, on_stack: true } => {
                     on_stack_param_indices.insert(argument_tys.len());
-                    arg.memory_ty(cx)
+                    // NOTE: the alignment of a byval argument can be higher than the one of its
+                    // type, so set it on the type of the parameter.
+                    let typ = arg.memory_ty(cx);
+                    match attrs.pointee_align {
+                        Some(align) => typ.get_aligned(align.bytes()),
+                        None => typ,
+                    }
                 },
-                PassMode::Indirect { extra_attrs: None, on_stack: false, .. } => cx.type_ptr_to(arg.memory_ty(cx)),
+                PassMode::Indirect { ref attrs, extra_attrs: None, on_stack: false } => apply_attrs(cx.type_ptr_to(arg.memory_ty(cx)), attrs),
             };
             argument_tys.push(arg_ty);
         }
@@ -7,6 +7,10 @@
 
 use crate::GccContext;
 
+/// Symbol that the `alloc` crate is meant to read before calling into the allocator, so that Rust
+/// code linked without the shim generated here fails to link instead of silently working.
+const NO_ALLOC_SHIM_IS_UNSTABLE: &str = "__rust_no_alloc_shim_is_unstable";
+
 pub(crate) unsafe fn codegen(tcx: TyCtxt<'_>, mods: &mut GccContext, _module_name: &str, kind: AllocatorKind, has_alloc_error_handler: bool) {
     let context = &mods.context;
     let usize =
@@ -111,13 +115,22 @@ pub(crate) unsafe fn codegen(tcx: TyCtxt<'_>, mods: &mut GccContext, _module_nam
         .enumerate()
         .map(|(i, _)| func.get_param(i as i32).to_rvalue())
         .collect::<Vec<_>>();
-    let _ret = context.new_call(None, callee, &args);
+    block.add_eval(None, context.new_call(None, callee, &args));
     //llvm::LLVMSetTailCall(ret, True);
+    // NOTE: the alloc error handler never returns.
+    let builtin_unreachable = context.get_builtin_function("__builtin_unreachable");
+    block.add_eval(None, context.new_call(None, builtin_unreachable, &[]));
     block.end_with_void_return(None);
 
+    // __rust_alloc_error_handler_should_panic
     let name = OomStrategy::SYMBOL.to_string();
     let global = context.new_global(None, GlobalKind::Exported, i8, name);
     let value = tcx.sess.opts.unstable_opts.oom.should_panic();
     let value = context.new_rvalue_from_int(i8, value as i32);
     global.global_set_initializer_rvalue(value);
+
+    let name = NO_ALLOC_SHIM_IS_UNSTABLE.to_string();
+    let global = context.new_global(None, GlobalKind::Exported, i8, name);
+    let value = context.new_rvalue_from_int(i8, 0);
+    global.global_set_initializer_rvalue(value);
 }
@@ -1,18 +1,21 @@
-use gccjit::{LValue, RValue, ToRValue, Type};
+use gccjit::{GlobalKind, LValue, RValue, ToRValue, Type};
 use rustc_ast::ast::{InlineAsmOptions, InlineAsmTemplatePiece};
 use rustc_codegen_ssa::mir::operand::OperandValue;
 use rustc_codegen_ssa::mir::place::PlaceRef;
-use rustc_codegen_ssa::traits::{AsmBuilderMethods, AsmMethods, BaseTypeMethods, BuilderMethods, GlobalAsmOperandRef, InlineAsmOperandRef};
+use rustc_codegen_ssa::traits::{AsmBuilderMethods, AsmMethods, BaseTypeMethods, BuilderMethods, DerivedTypeMethods, GlobalAsmOperandRef, InlineAsmOperandRef};
 
-use rustc_middle::{bug, ty::Instance};
-use rustc_span::Span;
+use rustc_hir::def_id::DefId;
+use rustc_middle::bug;
+use rustc_middle::ty::{Instance, TyCtxt};
+use rustc_session::Session;
+use rustc_span::{Span, Symbol};
 use rustc_target::asm::*;
 
 use std::borrow::Cow;
 
 use crate::builder::Builder;
 use crate::context::CodegenCx;
-use crate::errors::UnwindingInlineAsm;
+use crate::errors::{UnsupportedAsmClobber, UnsupportedAsmRegisterClass, UnwindingInlineAsm, WasmInlineAsm};
 use crate::type_of::LayoutGccExt;
 use crate::callee::get_fn;
 
@@ -65,8 +68,14 @@
 //                                              with `"r"(var)` constraint,
 //                                              and one register variable assigned to the desired register.
 
-const ATT_SYNTAX_INS: &str = ".att_syntax noprefix\n\t";
-const INTEL_SYNTAX_INS: &str = "\n\t.intel_syntax noprefix";
+// NOTE: on x86, GCC outputs the first alternative of `{att|intel}` when the ambient dialect is
+// AT&T (`-masm=att`) and the second one when it is Intel (`-masm=intel`), so the syntax is only
+// switched when the template doesn't use the ambient one. The alternative that starts the template
+// is closed after the line marker of its first line.
+const ATT_SYNTAX_INS: &str = "{|.att_syntax noprefix\n";
+const ATT_SYNTAX_END: &str = "{|\n\t.intel_syntax noprefix}";
+const INTEL_SYNTAX_INS: &str = "{.intel_syntax noprefix\n";
+const INTEL_SYNTAX_END: &str = "{\n\t.att_syntax|}";
 
 
 struct AsmOutOperand<'a, 'tcx, 'gcc> {
@@ -107,7 +116,7 @@ enum ConstraintOrRegister {
 
 
 impl<'a, 'gcc, 'tcx> AsmBuilderMethods<'tcx> for Builder<'a, 'gcc, 'tcx> {
-    fn codegen_inline_asm(&mut self, template: &[InlineAsmTemplatePiece], rust_operands: &[InlineAsmOperandRef<'tcx, Self>], options: InlineAsmOptions, span: &[Span], _instance: Instance<'_>, _dest_catch_funclet: Option<(Self::BasicBlock, Self::BasicBlock, Option<&Self::Funclet>)>) {
+    fn codegen_inline_asm(&mut self, template: &[InlineAsmTemplatePiece], rust_operands: &[InlineAsmOperandRef<'tcx, Self>], options: InlineAsmOptions, span: &[Span], instance: Instance<'_>, _dest_catch_funclet: Option<(Self::BasicBlock, Self::BasicBlock, Option<&Self::Funclet>)>) {
         if options.contains(InlineAsmOptions::MAY_UNWIND) {
             self.sess()
                 .create_err(UnwindingInlineAsm { span: span[0] })
@@ -116,9 +125,42 @@ fn codegen_inline_asm(&mut self, template: &[InlineAsmTemplatePiece], rust_opera
         }
 
         let asm_arch = self.tcx.sess.asm_arch.unwrap();
+        // NOTE: GCC doesn't have a wasm backend, so there's nothing to lower the `local` operands to.
+        if matches!(asm_arch, InlineAsmArch::Wasm32 | InlineAsmArch::Wasm64) {
+            self.sess()
+                .create_err(WasmInlineAsm { span: span[0] })
+                .emit();
+            return;
+        }
+
+        // Report the register classes that are not lowered yet instead of panicking, so that the
+        // errors in the rest of the crate still get reported.
+        let unsupported_reg_class = rust_operands.iter()
+            .filter_map(|op| match *op {
+                InlineAsmOperandRef::In { reg, .. }
+                | InlineAsmOperandRef::Out { reg, .. }
+                | InlineAsmOperandRef::InOut { reg, .. } => Some(reg.reg_class()),
+                _ => None,
+            })
+            .find(|&reg_class| !is_reg_class_supported(reg_class));
+        if let Some(reg_class) = unsupported_reg_class {
+            self.sess()
+                .create_err(UnsupportedAsmRegisterClass { span: span[0], reg_class: reg_class.name() })
+                .emit();
+            return;
+        }
+
         let is_x86 = matches!(asm_arch, InlineAsmArch::X86 | InlineAsmArch::X86_64);
         let att_dialect = is_x86 && options.contains(InlineAsmOptions::ATT_SYNTAX);
 
+        // Like in the LLVM backend, the registers enabled by `#[target_feature]` must be clobbered
+        // too, so that calling a function using them from the asm is sound.
+        let tcx = self.tcx;
+        let codegen_fn_attrs = tcx.codegen_fn_attrs(instance.def_id());
+        let has_target_feature = |feature: Symbol| {
+            tcx.sess.target_features.contains(&feature) || codegen_fn_attrs.target_features.contains(&feature)
+        };
+
         // GCC index of an output operand equals its position in the array
         let mut outputs = vec![];
 
@@ -157,7 +199,7 @@ fn codegen_inline_asm(&mut self, template: &[InlineAsmTemplatePiece], rust_opera
                     use ConstraintOrRegister::*;
 
                     let (constraint, ty) = match (reg_to_gcc(reg), place) {
-                        (Constraint(constraint), Some(place)) => (constraint, place.layout.gcc_type(self.cx, false)),
+                        (Constraint(constraint), Some(place)) => (constraint, asm_operand_type(self.cx, place.layout.gcc_type(self.cx, false))),
                         // When `reg` is a class and not an explicit register but the out place is not specified,
                         // we need to create an unused output variable to assign the output to. This var
                         // needs to be of a type that's "compatible" with the register class, but specific type
@@ -170,17 +212,55 @@ fn codegen_inline_asm(&mut self, template: &[InlineAsmTemplatePiece], rust_opera
                         (Register(reg_name), None) => {
                             // `clobber_abi` can add lots of clobbers that are not supported by the target,
                             // such as AVX-512 registers, so we just ignore unsupported registers
-                            let is_target_supported = reg.reg_class().supported_types(asm_arch).iter()
-                                .any(|&(_, feature)| {
-                                    if let Some(feature) = feature {
-                                        self.tcx.sess.target_features.contains(&feature)
-                                    } else {
-                                        true // Register class is unconditionally supported
-                                    }
-                                });
-
-                            if is_target_supported && !clobbers.contains(&reg_name) {
-                                clobbers.push(reg_name);
+                            let is_target_supported = match reg.reg_class() {
+                                // NOTE: clobber-only registers have no supported types to check the target
+                                // features against.
+                                InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::preg) => {
+                                    has_target_feature(Symbol::intern("sve"))
+                                },
+                                InlineAsmRegClass::RiscV(RiscVInlineAsmRegClass::vreg) => {
+                                    has_target_feature(Symbol::intern("v"))
+                                },
+                                InlineAsmRegClass::PowerPC(PowerPCInlineAsmRegClass::cr | PowerPCInlineAsmRegClass::xer) => true,
+                                InlineAsmRegClass::X86(X86InlineAsmRegClass::x87_reg | X86InlineAsmRegClass::mmx_reg) => true,
+                                // NOTE: GCC doesn't know about the AMX tile registers, so they can't be clobbered,
+                                // but it never allocates them either.
+                                InlineAsmRegClass::X86(X86InlineAsmRegClass::tmm_reg) => false,
+                                InlineAsmRegClass::X86(X86InlineAsmRegClass::kreg0) => {
+                                    has_target_feature(Symbol::intern("avx512f"))
+                                },
+                                reg_class => reg_class.supported_types(asm_arch).iter()
+                                    .any(|&(_, feature)| {
+                                        if let Some(feature) = feature {
+                                            has_target_feature(feature)
+                                        } else {
+                                            true // Register class is unconditionally supported
+                                        }
+                                    }),
+                            };
+                            // NOTE: `clobber_abi` only generates late outputs, so a clobber that isn't late was
+                            // written by the user: warn instead of silently ignoring it, since it could hide a bug.
+                            if !is_target_supported && !late {
+                                self.sess().emit_warning(UnsupportedAsmClobber { span: span[0], reg: reg.to_string() });
+                            }
+
+                            let reg_names: &[&'static str] = match reg.reg_class() {
+                                // NOTE: GCC has no name for the whole PowerPC condition register, so
+                                // clobber each of its fields instead.
+                                _ if reg == InlineAsmRegOrRegClass::Reg(InlineAsmReg::PowerPC(PowerPCInlineAsmReg::cr)) => {
+                                    &["cr0", "cr1", "cr2", "cr3", "cr4", "cr5", "cr6", "cr7"]
+                                },
+                                // NOTE: the x87 registers are accessed as a stack, so clobbering one
+                                // of them clobbers the whole stack.
+                                InlineAsmRegClass::X86(X86InlineAsmRegClass::x87_reg) => {
+                                    &["st", "st(1)", "st(2)", "st(3)", "st(4)", "st(5)", "st(6)", "st(7)"]
+                                },
+                                _ => std::slice::from_ref(&reg_name),
+                            };
+                            for &reg_name in reg_names {
+                                if is_target_supported && !clobbers.contains(&reg_name) {
+                                    clobbers.push(reg_name);
+                                }
                             }
                             continue
                         }
@@ -202,7 +282,7 @@ fn codegen_inline_asm(&mut self, template: &[InlineAsmTemplatePiece], rust_opera
                         inputs.push(AsmInOperand {
                             constraint: Cow::Borrowed(constraint),
                             rust_idx,
-                            val: value.immediate()
+                            val: asm_operand_value(self.cx, value.immediate())
                         });
                     }
                     else {
@@ -226,7 +306,7 @@ fn codegen_inline_asm(&mut self, template: &[InlineAsmTemplatePiece], rust_opera
                     // This decision is also backed by the fact that LLVM needs in and out
                     // values to be of *exactly the same type*, not just "compatible".
                     // I'm not sure if GCC is so picky too, but better safe than sorry.
-                    let ty = in_value.layout.gcc_type(self.cx, false);
+                    let ty = asm_operand_type(self.cx, in_value.layout.gcc_type(self.cx, false));
                     let tmp_var = self.current_func().new_local(None, ty, "output_register");
 
                     // If the out_place is None (i.e `inout(reg) _` syntax was used), we translate
@@ -249,7 +329,7 @@ fn codegen_inline_asm(&mut self, template: &[InlineAsmTemplatePiece], rust_opera
                         inputs.push(AsmInOperand {
                             constraint,
                             rust_idx,
-                            val: in_value.immediate()
+                            val: asm_operand_value(self.cx, in_value.immediate())
                         });
                     }
                 }
@@ -259,15 +339,10 @@ fn codegen_inline_asm(&mut self, template: &[InlineAsmTemplatePiece], rust_opera
                 }
 
                 InlineAsmOperandRef::SymFn { instance } => {
-                    // TODO(@Amanieu): Additional mangling is needed on
-                    // some targets to add a leading underscore (Mach-O)
-                    // or byte count suffixes (x86 Windows).
-                    constants_len += self.tcx.symbol_name(instance).name.len();
+                    constants_len += sym_fn_name(self.tcx, instance).len();
                 }
                 InlineAsmOperandRef::SymStatic { def_id } => {
-                    // TODO(@Amanieu): Additional mangling is needed on
-                    // some targets to add a leading underscore (Mach-O).
-                    constants_len += self.tcx.symbol_name(Instance::mono(self.tcx, def_id)).name.len();
+                    constants_len += sym_static_name(self.tcx, def_id).len();
                 }
             }
         }
@@ -286,12 +361,12 @@ fn codegen_inline_asm(&mut self, template: &[InlineAsmTemplatePiece], rust_opera
                             continue
                         };
 
-                        let ty = out_place.layout.gcc_type(self.cx, false);
+                        let ty = asm_operand_type(self.cx, out_place.layout.gcc_type(self.cx, false));
                         let tmp_var = self.current_func().new_local(None, ty, "output_register");
                         tmp_var.set_register_name(reg_name);
 
                         outputs.push(AsmOutOperand {
-                            constraint: "r".into(),
+                            constraint: explicit_reg_constraint(reg.reg_class()).into(),
                             rust_idx,
                             late,
                             readwrite: false,
@@ -306,13 +381,13 @@ fn codegen_inline_asm(&mut self, template: &[InlineAsmTemplatePiece], rust_opera
                 // `in("explicit register") var`
                 InlineAsmOperandRef::In { reg, value } => {
                     if let ConstraintOrRegister::Register(reg_name) = reg_to_gcc(reg) {
-                        let ty = value.layout.gcc_type(self.cx, false);
+                        let ty = asm_operand_type(self.cx, value.layout.gcc_type(self.cx, false));
                         let reg_var = self.current_func().new_local(None, ty, "input_register");
                         reg_var.set_register_name(reg_name);
-                        self.llbb().add_assignment(None, reg_var, value.immediate());
+                        self.llbb().add_assignment(None, reg_var, asm_operand_value(self.cx, value.immediate()));
 
                         inputs.push(AsmInOperand {
-                            constraint: "r".into(),
+                            constraint: explicit_reg_constraint(reg.reg_class()).into(),
                             rust_idx,
                             val: reg_var.to_rvalue()
                         });
@@ -325,12 +400,12 @@ fn codegen_inline_asm(&mut self, template: &[InlineAsmTemplatePiece], rust_opera
                 InlineAsmOperandRef::InOut { reg, late, in_value, out_place } => {
                     if let ConstraintOrRegister::Register(reg_name) = reg_to_gcc(reg) {
                         // See explanation in the first pass.
-                        let ty = in_value.layout.gcc_type(self.cx, false);
+                        let ty = asm_operand_type(self.cx, in_value.layout.gcc_type(self.cx, false));
                         let tmp_var = self.current_func().new_local(None, ty, "output_register");
                         tmp_var.set_register_name(reg_name);
 
                         outputs.push(AsmOutOperand {
-                            constraint: "r".into(),
+                            constraint: explicit_reg_constraint(reg.reg_class()).into(),
                             rust_idx,
                             late,
                             readwrite: false,
@@ -342,7 +417,7 @@ fn codegen_inline_asm(&mut self, template: &[InlineAsmTemplatePiece], rust_opera
                         inputs.push(AsmInOperand {
                             constraint,
                             rust_idx,
-                            val: in_value.immediate()
+                            val: asm_operand_value(self.cx, in_value.immediate())
                         });
                     }
 
@@ -372,25 +447,57 @@ fn codegen_inline_asm(&mut self, template: &[InlineAsmTemplatePiece], rust_opera
             }
         }
 
+        // NOTE: when the asm block may use the stack, make the stack pointer a read-write operand, in
+        // the same way as the ASM_CALL_CONSTRAINT of the Linux kernel. This forces GCC to set up the
+        // stack frame (and thus to keep the stack aligned) before the asm block, even in leaf functions.
+        // Since it's pushed after the other outputs, it doesn't change the index of any Rust operand.
+        // With `nostack`, no such operand is added so GCC is free to omit the frame.
+        // TODO: do the same on other architectures.
+        if !options.contains(InlineAsmOptions::NOSTACK) {
+            if let Some(tmp_var) = self.cx.stack_pointer(asm_arch) {
+                outputs.push(AsmOutOperand {
+                    constraint: "r",
+                    // Not tied to any Rust operand.
+                    rust_idx: usize::MAX,
+                    late: true,
+                    readwrite: true,
+                    tmp_var,
+                    out_place: None,
+                });
+            }
+        }
+
         // 3. Build the template string
 
-        let mut template_str = String::with_capacity(estimate_template_length(template, constants_len, att_dialect));
+        // NOTE: GCC precedes the asm with a line marker for the location of the extended asm, so that
+        // the assembler reports its errors at the Rust source. Since the lines of the template don't
+        // necessarily follow each other in the source, add a line marker for each of them. The first
+        // line only needs one when it's shifted by a syntax directive.
+        let mut template_str = String::with_capacity(estimate_template_length(template, constants_len, is_x86));
         if att_dialect {
             template_str.push_str(ATT_SYNTAX_INS);
+            push_escaped(&mut template_str, &line_marker(self.cx, span[0]), is_x86);
+            template_str.push('}');
         }
+        else if is_x86 {
+            template_str.push_str(INTEL_SYNTAX_INS);
+            push_escaped(&mut template_str, &line_marker(self.cx, span[0]), is_x86);
+            template_str.push_str("|}");
+        }
+        let mut line = 0;
 
         for piece in template {
             match *piece {
                 InlineAsmTemplatePiece::String(ref string) => {
-                    // TODO(@Commeownist): switch to `Iterator::intersperse` once it's stable
-                    let mut iter = string.split('%');
-                    if let Some(s) = iter.next() {
-                        template_str.push_str(s);
-                    }
-
-                    for s in iter {
-                        template_str.push_str("%%");
-                        template_str.push_str(s);
+                    for (index, string_line) in string.split('\n').enumerate() {
+                        if index > 0 {
+                            template_str.push('\n');
+                            line += 1;
+                            if let Some(&line_span) = span.get(line) {
+                                push_escaped(&mut template_str, &line_marker(self.cx, line_span), is_x86);
+                            }
+                        }
+                        push_escaped(&mut template_str, string_line, is_x86);
                     }
                 }
                 InlineAsmTemplatePiece::Placeholder { operand_idx, modifier, span: _ } => {
@@ -434,18 +541,14 @@ fn codegen_inline_asm(&mut self, template: &[InlineAsmTemplatePiece], rust_opera
 
                         InlineAsmOperandRef::SymFn { instance } => {
                             // TODO(@Amanieu): Additional mangling is needed on
-                            // some targets to add a leading underscore (Mach-O)
-                            // or byte count suffixes (x86 Windows).
-                            let name = self.tcx.symbol_name(instance).name;
-                            template_str.push_str(name);
+                            // some targets to add a leading underscore (Mach-O).
+                            template_str.push_str(&sym_fn_name(self.tcx, instance));
                         }
 
                         InlineAsmOperandRef::SymStatic { def_id } => {
                             // TODO(@Amanieu): Additional mangling is needed on
                             // some targets to add a leading underscore (Mach-O).
-                            let instance = Instance::mono(self.tcx, def_id);
-                            let name = self.tcx.symbol_name(instance).name;
-                            template_str.push_str(name);
+                            template_str.push_str(&sym_static_name(self.tcx, def_id));
                         }
 
                         InlineAsmOperandRef::Const { ref string } => {
@@ -461,13 +564,16 @@ fn codegen_inline_asm(&mut self, template: &[InlineAsmTemplatePiece], rust_opera
         }
 
         if att_dialect {
-            template_str.push_str(INTEL_SYNTAX_INS);
+            template_str.push_str(ATT_SYNTAX_END);
+        }
+        else if is_x86 {
+            template_str.push_str(INTEL_SYNTAX_END);
         }
 
         // 4. Generate Extended Asm block
 
         let block = self.llbb();
-        let extended_asm = block.add_extended_asm(None, &template_str);
+        let extended_asm = block.add_extended_asm(Some(self.cx.span_to_location(span[0])), &template_str);
 
         for op in &outputs {
             extended_asm.add_output_operand(None, &op.to_constraint(), op.tmp_var);
@@ -483,18 +589,22 @@ fn codegen_inline_asm(&mut self, template: &[InlineAsmTemplatePiece], rust_opera
 
         if !options.contains(InlineAsmOptions::PRESERVES_FLAGS) {
             // TODO(@Commeownist): I'm not 100% sure this one clobber is sufficient
-            // on all architectures. For instance, what about FP stack?
+            // on all architectures.
             extended_asm.add_clobber("cc");
+            if is_x86 {
+                // NOTE: the direction flag and the x87 status word are flags too.
+                extended_asm.add_clobber("dirflag");
+                extended_asm.add_clobber("fpsr");
+            }
         }
         if !options.contains(InlineAsmOptions::NOMEM) {
+            // NOTE: GCC has no way to say that an asm only reads arbitrary memory without
+            // pointing to a real object, so readonly asm also clobbers memory.
             extended_asm.add_clobber("memory");
         }
         if !options.contains(InlineAsmOptions::PURE) {
             extended_asm.set_volatile_flag(true);
         }
-        if !options.contains(InlineAsmOptions::NOSTACK) {
-            // TODO(@Commeownist): figure out how to align stack
-        }
         if options.contains(InlineAsmOptions::NORETURN) {
             let builtin_unreachable = self.context.get_builtin_function("__builtin_unreachable");
             let builtin_unreachable: RValue<'gcc> = unsafe { std::mem::transmute(builtin_unreachable) };
@@ -512,14 +622,15 @@ fn codegen_inline_asm(&mut self, template: &[InlineAsmTemplatePiece], rust_opera
         // generates `out_place = tmp_var;` assignments if out_place exists.
         for op in &outputs {
             if let Some(place) = op.out_place {
-                OperandValue::Immediate(op.tmp_var.to_rvalue()).store(self, place);
+                let value = self.cx.bitcast_if_needed(op.tmp_var.to_rvalue(), place.layout.gcc_type(self.cx, false));
+                OperandValue::Immediate(value).store(self, place);
             }
         }
 
     }
 }
 
-fn estimate_template_length(template: &[InlineAsmTemplatePiece], constants_len: usize, att_dialect: bool) -> usize {
+fn estimate_template_length(template: &[InlineAsmTemplatePiece], constants_len: usize, is_x86: bool) -> usize {
     let len: usize = template.iter().map(|piece| {
         match *piece {
             InlineAsmTemplatePiece::String(ref string) => {
@@ -538,12 +649,34 @@ fn estimate_template_length(template: &[InlineAsmTemplatePiece], constants_len:
     // as the upper bound
     let mut res = (len as f32 * 1.05) as usize + constants_len;
 
-    if att_dialect {
-        res += INTEL_SYNTAX_INS.len() + ATT_SYNTAX_INS.len();
+    if is_x86 {
+        res += ATT_SYNTAX_INS.len() + ATT_SYNTAX_END.len();
     }
     res
 }
 
+const XMM_REG_NAMES: [&str; 32] = [
+    "xmm0", "xmm1", "xmm2", "xmm3", "xmm4", "xmm5", "xmm6", "xmm7",
+    "xmm8", "xmm9", "xmm10", "xmm11", "xmm12", "xmm13", "xmm14", "xmm15",
+    "xmm16", "xmm17", "xmm18", "xmm19", "xmm20", "xmm21", "xmm22", "xmm23",
+    "xmm24", "xmm25", "xmm26", "xmm27", "xmm28", "xmm29", "xmm30", "xmm31",
+];
+
+/// Returns the constraint to use for the register variable of an explicit register: it must
+/// match the class of the register, otherwise GCC can't allocate the variable to it.
+fn explicit_reg_constraint(reg: InlineAsmRegClass) -> &'static str {
+    match reg_to_gcc(InlineAsmRegOrRegClass::RegClass(reg)) {
+        ConstraintOrRegister::Constraint(constraint) => constraint,
+        ConstraintOrRegister::Register(_) => unreachable!(),
+    }
+}
+
+/// Returns whether the register class can be lowered to GCC. Explicit registers of an unsupported
+/// class are rejected as well.
+fn is_reg_class_supported(reg: InlineAsmRegClass) -> bool {
+    !matches!(reg, InlineAsmRegClass::Avr(_) | InlineAsmRegClass::Bpf(_) | InlineAsmRegClass::Msp430(_))
+}
+
 /// Converts a register class to a GCC constraint code.
 fn reg_to_gcc(reg: InlineAsmRegOrRegClass) -> ConstraintOrRegister {
     let constraint = match reg {
@@ -551,53 +684,72 @@ fn reg_to_gcc(reg: InlineAsmRegOrRegClass) -> ConstraintOrRegister {
         InlineAsmRegOrRegClass::Reg(reg) => {
             match reg {
                 InlineAsmReg::X86(_) => {
-                    // TODO(antoyo): add support for vector register.
-                    //
-                    // // For explicit registers, we have to create a register variable: https://stackoverflow.com/a/31774784/389119
+                    // For explicit registers, we have to create a register variable: https://stackoverflow.com/a/31774784/389119
                     return ConstraintOrRegister::Register(match reg.name() {
                         // Some of registers' names does not map 1-1 from rust to gcc
                         "st(0)" => "st",
+                        // NOTE: GCC only knows the vector registers by their xmm name: the width
+                        // of the register is given by the type of the register variable.
+                        name if name.starts_with("ymm") || name.starts_with("zmm") => {
+                            let index: usize = name[3..].parse().expect("vector register index");
+                            XMM_REG_NAMES[index]
+                        },
 
                         name => name,
                     });
                 }
+                // NOTE: the MIPS register names already use the `$` prefix that GCC expects.
+                InlineAsmReg::AArch64(_)
+                | InlineAsmReg::Arm(_)
+                | InlineAsmReg::Hexagon(_)
+                | InlineAsmReg::Mips(_)
+                | InlineAsmReg::PowerPC(_)
+                | InlineAsmReg::RiscV(_)
+                | InlineAsmReg::S390x(_) => {
+                    return ConstraintOrRegister::Register(reg.name());
+                }
 
-                _ => unimplemented!(),
+                _ => unreachable!("unsupported register"),
             }
         },
         InlineAsmRegOrRegClass::RegClass(reg) => match reg {
-            InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::preg) => unimplemented!(),
-            InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::reg) => unimplemented!(),
-            InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::vreg) => unimplemented!(),
-            InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::vreg_low16) => unimplemented!(),
-            InlineAsmRegClass::Arm(ArmInlineAsmRegClass::reg) => unimplemented!(),
+            InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::preg) => unreachable!("clobber-only"),
+            InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::reg) => "r",
+            InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::vreg) => "w",
+            InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::vreg_low16) => "x",
+            InlineAsmRegClass::Arm(ArmInlineAsmRegClass::reg) => "r",
             InlineAsmRegClass::Arm(ArmInlineAsmRegClass::sreg)
             | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::dreg_low16)
-            | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::qreg_low8) => unimplemented!(),
+            | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::qreg_low8) => "t",
             InlineAsmRegClass::Arm(ArmInlineAsmRegClass::sreg_low16)
             | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::dreg_low8)
-            | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::qreg_low4) => unimplemented!(),
+            | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::qreg_low4) => "x",
             InlineAsmRegClass::Arm(ArmInlineAsmRegClass::dreg)
-            | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::qreg) => unimplemented!(),
-            InlineAsmRegClass::Avr(_) => unimplemented!(),
-            InlineAsmRegClass::Bpf(_) => unimplemented!(),
-            InlineAsmRegClass::Hexagon(HexagonInlineAsmRegClass::reg) => unimplemented!(),
-            InlineAsmRegClass::Mips(MipsInlineAsmRegClass::reg) => unimplemented!(),
-            InlineAsmRegClass::Mips(MipsInlineAsmRegClass::freg) => unimplemented!(),
-            InlineAsmRegClass::Msp430(_) => unimplemented!(),
-            InlineAsmRegClass::Nvptx(NvptxInlineAsmRegClass::reg16) => unimplemented!(),
-            InlineAsmRegClass::Nvptx(NvptxInlineAsmRegClass::reg32) => unimplemented!(),
-            InlineAsmRegClass::Nvptx(NvptxInlineAsmRegClass::reg64) => unimplemented!(),
-            InlineAsmRegClass::PowerPC(PowerPCInlineAsmRegClass::reg) => unimplemented!(),
-            InlineAsmRegClass::PowerPC(PowerPCInlineAsmRegClass::reg_nonzero) => unimplemented!(),
-            InlineAsmRegClass::PowerPC(PowerPCInlineAsmRegClass::freg) => unimplemented!(),
+            | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::qreg) => "w",
+            InlineAsmRegClass::Avr(_) => unreachable!("unsupported register class"),
+            InlineAsmRegClass::Bpf(_) => unreachable!("unsupported register class"),
+            // NOTE: upstream GCC has no Hexagon port, so there is no assembly test for this class.
+            InlineAsmRegClass::Hexagon(HexagonInlineAsmRegClass::reg) => "r",
+            InlineAsmRegClass::Mips(MipsInlineAsmRegClass::reg) => "r",
+            InlineAsmRegClass::Mips(MipsInlineAsmRegClass::freg) => "f",
+            InlineAsmRegClass::Msp430(_) => unreachable!("unsupported register class"),
+            // NOTE: unlike NVCC, GCC has no constraint per register width: the width of the PTX
+            // register comes from the type of the operand.
+            InlineAsmRegClass::Nvptx(NvptxInlineAsmRegClass::reg16)
+            | InlineAsmRegClass::Nvptx(NvptxInlineAsmRegClass::reg32)
+            | InlineAsmRegClass::Nvptx(NvptxInlineAsmRegClass::reg64) => "r",
+            InlineAsmRegClass::PowerPC(PowerPCInlineAsmRegClass::reg) => "r",
+            InlineAsmRegClass::PowerPC(PowerPCInlineAsmRegClass::reg_nonzero) => "b",
+            InlineAsmRegClass::PowerPC(PowerPCInlineAsmRegClass::freg) => "f",
             InlineAsmRegClass::PowerPC(PowerPCInlineAsmRegClass::cr)
             | InlineAsmRegClass::PowerPC(PowerPCInlineAsmRegClass::xer) => {
                 unreachable!("clobber-only")
             },
-            InlineAsmRegClass::RiscV(RiscVInlineAsmRegClass::reg) => unimplemented!(),
-            InlineAsmRegClass::RiscV(RiscVInlineAsmRegClass::freg) => unimplemented!(),
-            InlineAsmRegClass::RiscV(RiscVInlineAsmRegClass::vreg) => unimplemented!(),
+            InlineAsmRegClass::RiscV(RiscVInlineAsmRegClass::reg) => "r",
+            InlineAsmRegClass::RiscV(RiscVInlineAsmRegClass::freg) => "f",
+            // NOTE: the frontend currently only accepts vector registers as clobbers, but this is
+            // the constraint GCC uses for them.
+            InlineAsmRegClass::RiscV(RiscVInlineAsmRegClass::vreg) => "vr",
             InlineAsmRegClass::X86(X86InlineAsmRegClass::reg) => "r",
             InlineAsmRegClass::X86(X86InlineAsmRegClass::reg_abcd) => "Q",
             InlineAsmRegClass::X86(X86InlineAsmRegClass::reg_byte) => "q",
@@ -605,16 +757,19 @@ fn reg_to_gcc(reg: InlineAsmRegOrRegClass) -> ConstraintOrRegister {
             | InlineAsmRegClass::X86(X86InlineAsmRegClass::ymm_reg) => "x",
             InlineAsmRegClass::X86(X86InlineAsmRegClass::zmm_reg) => "v",
             InlineAsmRegClass::X86(X86InlineAsmRegClass::kreg) => "Yk",
-            InlineAsmRegClass::X86(X86InlineAsmRegClass::kreg0) => unimplemented!(),
-            InlineAsmRegClass::Wasm(WasmInlineAsmRegClass::local) => unimplemented!(),
+            // NOTE: unlike "Yk", the "k" constraint includes k0.
+            InlineAsmRegClass::X86(X86InlineAsmRegClass::kreg0) => "k",
+            InlineAsmRegClass::Wasm(WasmInlineAsmRegClass::local) => {
+                bug!("GCC backend does not support inline asm on wasm")
+            }
             InlineAsmRegClass::X86(
                 X86InlineAsmRegClass::x87_reg | X86InlineAsmRegClass::mmx_reg | X86InlineAsmRegClass::tmm_reg,
             ) => unreachable!("clobber-only"),
             InlineAsmRegClass::SpirV(SpirVInlineAsmRegClass::reg) => {
                 bug!("GCC backend does not support SPIR-V")
             }
-            InlineAsmRegClass::S390x(S390xInlineAsmRegClass::reg) => unimplemented!(),
-            InlineAsmRegClass::S390x(S390xInlineAsmRegClass::freg) => unimplemented!(),
+            InlineAsmRegClass::S390x(S390xInlineAsmRegClass::reg) => "r",
+            InlineAsmRegClass::S390x(S390xInlineAsmRegClass::freg) => "f",
             InlineAsmRegClass::Err => unreachable!(),
         }
     };
@@ -622,15 +777,128 @@ fn reg_to_gcc(reg: InlineAsmRegOrRegClass) -> ConstraintOrRegister {
     ConstraintOrRegister::Constraint(constraint)
 }
 
+/// 128-bit integers are emulated with arrays when GCC doesn't support them, but arrays can't be
+/// asm operands. Returns the type to use instead for such operands: a vector of two 64-bit
+/// integers, which fits in the vector registers, the only ones that accept 128-bit integers.
+fn asm_operand_type<'gcc>(cx: &CodegenCx<'gcc, '_>, ty: Type<'gcc>) -> Type<'gcc> {
+    if cx.is_non_native_int_type(ty) {
+        cx.context.new_vector_type(cx.u64_type, 2)
+    }
+    else {
+        ty
+    }
+}
+
+/// Converts `value` to the type returned by `asm_operand_type`.
+fn asm_operand_value<'gcc>(cx: &CodegenCx<'gcc, '_>, value: RValue<'gcc>) -> RValue<'gcc> {
+    cx.bitcast_if_needed(value, asm_operand_type(cx, value.get_type()))
+}
+
+/// Appends `string` to the template of an extended asm, escaping the characters that are special
+/// to GCC.
+fn push_escaped(template_str: &mut String, string: &str, is_x86: bool) {
+    for c in string.chars() {
+        match c {
+            '%' => template_str.push_str("%%"),
+            // NOTE: on x86, these characters delimit the dialect alternatives, so they need to be
+            // escaped as well.
+            '{' | '|' | '}' if is_x86 => {
+                template_str.push('%');
+                template_str.push(c);
+            },
+            _ => template_str.push(c),
+        }
+    }
+}
+
+/// Returns a line marker that makes the assembler consider that the next line comes from `span`.
+fn line_marker(cx: &CodegenCx<'_, '_>, span: Span) -> String {
+    let source_map = cx.sess().source_map();
+    let loc = source_map.lookup_char_pos(span.lo());
+    // NOTE: use the same file name as span_to_location since it ends up in the debuginfo as well.
+    let filename = loc.file.name.prefer_remapped();
+    format!("# {} \"{}\"\n", loc.line, filename)
+}
+
+/// Removes the `//` comment of a line of global asm. Those are only searched for outside of string
+/// and character literals and before the native line comment of the architecture, which the
+/// assembler handles by itself.
+fn strip_comment(line: &str, arch: InlineAsmArch) -> &str {
+    let native_comment =
+        match arch {
+            InlineAsmArch::X86 | InlineAsmArch::X86_64 | InlineAsmArch::RiscV32 | InlineAsmArch::RiscV64
+            | InlineAsmArch::Mips | InlineAsmArch::Mips64 | InlineAsmArch::PowerPC | InlineAsmArch::PowerPC64
+            | InlineAsmArch::S390x => Some('#'),
+            InlineAsmArch::Arm => Some('@'),
+            // NOTE: `//` is the native comment on the other architectures, so stripping it is harmless.
+            _ => None,
+        };
+
+    let mut in_string = false;
+    let mut chars = line.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' if in_string => {
+                // Skip the escaped character.
+                chars.next();
+            },
+            '"' => in_string = !in_string,
+            '\'' if !in_string => {
+                // NOTE: a character literal is written 'c or 'c' for the assembler, so skip the
+                // character, which can be a quote or a slash, and the optional closing quote.
+                if let Some((_, '\\')) = chars.next() {
+                    chars.next();
+                }
+                chars.next_if(|&(_, c)| c == '\'');
+            },
+            '/' if !in_string && matches!(chars.peek(), Some(&(_, '/'))) => return &line[..index],
+            _ if !in_string && Some(c) == native_comment => return line,
+            _ => (),
+        }
+    }
+    line
+}
+
+/// Returns whether GCC outputs AT&T syntax on x86, i.e. whether `-masm=att` was passed to override
+/// the `-masm=intel` option set in `base.rs`.
+fn is_att_ambient_dialect(sess: &Session) -> bool {
+    sess.opts.cg.llvm_args.iter().rev()
+        .find_map(|arg| arg.strip_prefix("-masm="))
+        == Some("att")
+}
+
+/// Name of the function referred to by a `sym` operand, as it must be written in the assembly.
+fn sym_fn_name<'tcx>(tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> String {
+    decorate_sym_name(tcx, tcx.symbol_name(instance).name)
+}
+
+/// Name of the static referred to by a `sym` operand, as it must be written in the assembly.
+fn sym_static_name(tcx: TyCtxt<'_>, def_id: DefId) -> String {
+    decorate_sym_name(tcx, tcx.symbol_name(Instance::mono(tcx, def_id)).name)
+}
+
+/// Adds the leading underscore that symbols have on 32-bit x86 Windows.
+fn decorate_sym_name(tcx: TyCtxt<'_>, name: &str) -> String {
+    // NOTE: the `@N` suffixes of the stdcall, fastcall and vectorcall conventions are never
+    // needed since the functions using those conventions can be neither defined nor called.
+    let target = &tcx.sess.target;
+    if target.is_like_windows && target.arch == "x86" {
+        format!("_{}", name)
+    }
+    else {
+        name.to_string()
+    }
+}
+
 /// Type to use for outputs that are discarded. It doesn't really matter what
 /// the type is, as long as it is valid for the constraint code.
 fn dummy_output_type<'gcc, 'tcx>(cx: &CodegenCx<'gcc, 'tcx>, reg: InlineAsmRegClass) -> Type<'gcc> {
     match reg {
-        InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::reg) => cx.type_i32(),
-        InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::preg) => unimplemented!(),
+        InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::reg) => cx.type_i64(),
+        InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::preg) => unreachable!("clobber-only"),
         InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::vreg)
         | InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::vreg_low16) => {
-            unimplemented!()
+            cx.type_vector(cx.type_f32(), 4)
         }
         InlineAsmRegClass::Arm(ArmInlineAsmRegClass::reg)=> cx.type_i32(),
         InlineAsmRegClass::Arm(ArmInlineAsmRegClass::sreg)
@@ -641,14 +909,14 @@ fn dummy_output_type<'gcc, 'tcx>(cx: &CodegenCx<'gcc, 'tcx>, reg: InlineAsmRegCl
         InlineAsmRegClass::Arm(ArmInlineAsmRegClass::qreg)
         | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::qreg_low8)
         | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::qreg_low4) => {
-            unimplemented!()
+            cx.type_vector(cx.type_i64(), 2)
         }
-        InlineAsmRegClass::Avr(_) => unimplemented!(),
-        InlineAsmRegClass::Bpf(_) => unimplemented!(),
+        InlineAsmRegClass::Avr(_) => unreachable!("unsupported register class"),
+        InlineAsmRegClass::Bpf(_) => unreachable!("unsupported register class"),
         InlineAsmRegClass::Hexagon(HexagonInlineAsmRegClass::reg) => cx.type_i32(),
         InlineAsmRegClass::Mips(MipsInlineAsmRegClass::reg) => cx.type_i32(),
         InlineAsmRegClass::Mips(MipsInlineAsmRegClass::freg) => cx.type_f32(),
-        InlineAsmRegClass::Msp430(_) => unimplemented!(),
+        InlineAsmRegClass::Msp430(_) => unreachable!("unsupported register class"),
         InlineAsmRegClass::Nvptx(NvptxInlineAsmRegClass::reg16) => cx.type_i16(),
         InlineAsmRegClass::Nvptx(NvptxInlineAsmRegClass::reg32) => cx.type_i32(),
         InlineAsmRegClass::Nvptx(NvptxInlineAsmRegClass::reg64) => cx.type_i64(),
@@ -661,18 +929,18 @@ fn dummy_output_type<'gcc, 'tcx>(cx: &CodegenCx<'gcc, 'tcx>, reg: InlineAsmRegCl
         },
         InlineAsmRegClass::RiscV(RiscVInlineAsmRegClass::reg) => cx.type_i32(),
         InlineAsmRegClass::RiscV(RiscVInlineAsmRegClass::freg) => cx.type_f32(),
-        InlineAsmRegClass::RiscV(RiscVInlineAsmRegClass::vreg) => cx.type_f32(),
+        InlineAsmRegClass::RiscV(RiscVInlineAsmRegClass::vreg) => unreachable!("clobber-only"),
         InlineAsmRegClass::X86(X86InlineAsmRegClass::reg)
         | InlineAsmRegClass::X86(X86InlineAsmRegClass::reg_abcd) => cx.type_i32(),
         InlineAsmRegClass::X86(X86InlineAsmRegClass::reg_byte) => cx.type_i8(),
-        InlineAsmRegClass::X86(X86InlineAsmRegClass::mmx_reg) => unimplemented!(),
+        InlineAsmRegClass::X86(X86InlineAsmRegClass::mmx_reg) => unreachable!("clobber-only"),
         InlineAsmRegClass::X86(X86InlineAsmRegClass::xmm_reg)
         | InlineAsmRegClass::X86(X86InlineAsmRegClass::ymm_reg)
         | InlineAsmRegClass::X86(X86InlineAsmRegClass::zmm_reg) => cx.type_f32(),
-        InlineAsmRegClass::X86(X86InlineAsmRegClass::x87_reg) => unimplemented!(),
+        InlineAsmRegClass::X86(X86InlineAsmRegClass::x87_reg) => unreachable!("clobber-only"),
         InlineAsmRegClass::X86(X86InlineAsmRegClass::kreg) => cx.type_i16(),
         InlineAsmRegClass::X86(X86InlineAsmRegClass::kreg0) => cx.type_i16(),
-        InlineAsmRegClass::X86(X86InlineAsmRegClass::tmm_reg) => unimplemented!(),
+        InlineAsmRegClass::X86(X86InlineAsmRegClass::tmm_reg) => unreachable!("clobber-only"),
         InlineAsmRegClass::Wasm(WasmInlineAsmRegClass::local) => cx.type_i32(),
         InlineAsmRegClass::SpirV(SpirVInlineAsmRegClass::reg) => {
             bug!("LLVM backend does not support SPIR-V")
@@ -684,12 +952,12 @@ fn dummy_output_type<'gcc, 'tcx>(cx: &CodegenCx<'gcc, 'tcx>, reg: InlineAsmRegCl
 }
 
 impl<'gcc, 'tcx> AsmMethods<'tcx> for CodegenCx<'gcc, 'tcx> {
-    fn codegen_global_asm(&self, template: &[InlineAsmTemplatePiece], operands: &[GlobalAsmOperandRef<'tcx>], options: InlineAsmOptions, _line_spans: &[Span]) {
+    fn codegen_global_asm(&self, template: &[InlineAsmTemplatePiece], operands: &[GlobalAsmOperandRef<'tcx>], options: InlineAsmOptions, line_spans: &[Span]) {
         let asm_arch = self.tcx.sess.asm_arch.unwrap();
 
         // Default to Intel syntax on x86
-        let att_dialect = matches!(asm_arch, InlineAsmArch::X86 | InlineAsmArch::X86_64)
-            && options.contains(InlineAsmOptions::ATT_SYNTAX);
+        let is_x86 = matches!(asm_arch, InlineAsmArch::X86 | InlineAsmArch::X86_64);
+        let att_dialect = is_x86 && options.contains(InlineAsmOptions::ATT_SYNTAX);
 
         // Build the template string
         let mut template_str = String::new();
@@ -698,14 +966,7 @@ fn codegen_global_asm(&self, template: &[InlineAsmTemplatePiece], operands: &[Gl
                 InlineAsmTemplatePiece::String(ref string) => {
                     for line in string.lines() {
                         // NOTE: gcc does not allow inline comment, so remove them.
-                        let line =
-                            if let Some(index) = line.rfind("//") {
-                                &line[..index]
-                            }
-                            else {
-                                line
-                            };
-                        template_str.push_str(line);
+                        template_str.push_str(strip_comment(line, asm_arch));
                         template_str.push('\n');
                     }
                 },
@@ -720,66 +981,127 @@ fn codegen_global_asm(&self, template: &[InlineAsmTemplatePiece], operands: &[Gl
 
                         GlobalAsmOperandRef::SymFn { instance } => {
                             // TODO(@Amanieu): Additional mangling is needed on
-                            // some targets to add a leading underscore (Mach-O)
-                            // or byte count suffixes (x86 Windows).
-                            let name = self.tcx.symbol_name(instance).name;
-                            template_str.push_str(name);
+                            // some targets to add a leading underscore (Mach-O).
+                            template_str.push_str(&sym_fn_name(self.tcx, instance));
                         }
 
                         GlobalAsmOperandRef::SymStatic { def_id } => {
                             // TODO(@Amanieu): Additional mangling is needed on
                             // some targets to add a leading underscore (Mach-O).
-                            let instance = Instance::mono(self.tcx, def_id);
-                            let name = self.tcx.symbol_name(instance).name;
-                            template_str.push_str(name);
+                            template_str.push_str(&sym_static_name(self.tcx, def_id));
                         }
                     }
                 }
             }
         }
 
+        // NOTE: GCC doesn't add line markers around top-level asm, so add one to have the assembler
+        // report its errors at the Rust source.
+        let template_str = match line_spans.first() {
+            Some(&span) => format!("{}{}", line_marker(self, span), template_str),
+            None => template_str,
+        };
+        // NOTE: GCC doesn't process the dialect alternatives in top-level asm, so switch the syntax
+        // according to the ambient dialect instead.
         let template_str =
-            if att_dialect {
-                format!(".att_syntax\n\t{}\n\t.intel_syntax noprefix", template_str)
+            if att_dialect && !is_att_ambient_dialect(self.sess()) {
+                format!(".att_syntax\n{}\n\t.intel_syntax noprefix", template_str)
+            }
+            else if is_x86 && !att_dialect && is_att_ambient_dialect(self.sess()) {
+                format!(".intel_syntax noprefix\n{}\n\t.att_syntax", template_str)
             }
             else {
                 template_str
             };
         // NOTE: seems like gcc will put the asm in the wrong section, so set it to .text manually.
         let template_str = format!(".pushsection .text\n{}\n.popsection", template_str);
-        self.context.add_top_level_asm(None, &template_str);
+        let location = line_spans.first().map(|&span| self.span_to_location(span));
+        self.context.add_top_level_asm(location, &template_str);
+    }
+}
+
+impl<'gcc, 'tcx> CodegenCx<'gcc, 'tcx> {
+    /// Returns the global register variable for the stack pointer of the target, if any, and makes
+    /// sure the asm blocks can use the stack.
+    fn stack_pointer(&self, asm_arch: InlineAsmArch) -> Option<LValue<'gcc>> {
+        let reg_name =
+            match asm_arch {
+                InlineAsmArch::X86 => "esp",
+                InlineAsmArch::X86_64 => "rsp",
+                _ => return None,
+            };
+        if let Some(stack_pointer) = self.stack_pointer.get() {
+            return Some(stack_pointer);
+        }
+
+        // NOTE: the red zone is below the stack pointer, so a push in the asm block would clobber
+        // the values GCC keeps there. GCC cannot disable the red zone for a single function, so
+        // disable it for the whole codegen unit.
+        if asm_arch == InlineAsmArch::X86_64 && !self.sess().target.disable_redzone {
+            self.context.add_command_line_option("-mno-red-zone");
+        }
+
+        let stack_pointer = self.context.new_global(None, GlobalKind::Internal, self.type_i8p(), "__stack_pointer");
+        stack_pointer.set_register_name(reg_name);
+        self.stack_pointer.set(Some(stack_pointer));
+        Some(stack_pointer)
     }
 }
 
 fn modifier_to_gcc(arch: InlineAsmArch, reg: InlineAsmRegClass, modifier: Option<char>) -> Option<char> {
     match reg {
-        InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::reg) => modifier,
-        InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::preg) => modifier,
+        // NOTE: without a modifier, GCC picks the register width from the operand type, while Rust
+        // always wants the 64-bit x register.
+        InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::reg) => match modifier {
+            None | Some('x') => Some('x'),
+            Some('w') => Some('w'),
+            _ => unreachable!(),
+        },
+        InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::preg) => unreachable!("clobber-only"),
         InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::vreg)
-        | InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::vreg_low16) => {
-            unimplemented!()
-        }
-        InlineAsmRegClass::Arm(ArmInlineAsmRegClass::reg)  => unimplemented!(),
+        | InlineAsmRegClass::AArch64(AArch64InlineAsmRegClass::vreg_low16) => match modifier {
+            // NOTE: GCC prints the full v register when there's no modifier.
+            None | Some('v') => None,
+            Some('b') => Some('b'),
+            Some('h') => Some('h'),
+            Some('s') => Some('s'),
+            Some('d') => Some('d'),
+            Some('q') => Some('q'),
+            _ => unreachable!(),
+        },
+        InlineAsmRegClass::Arm(ArmInlineAsmRegClass::reg) => None,
         InlineAsmRegClass::Arm(ArmInlineAsmRegClass::sreg)
-        | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::sreg_low16) => unimplemented!(),
+        | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::sreg_low16) => None,
         InlineAsmRegClass::Arm(ArmInlineAsmRegClass::dreg)
         | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::dreg_low16)
-        | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::dreg_low8) => unimplemented!(),
+        | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::dreg_low8) => Some('P'),
         InlineAsmRegClass::Arm(ArmInlineAsmRegClass::qreg)
         | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::qreg_low8)
-        | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::qreg_low4) => {
-            unimplemented!()
-        }
-        InlineAsmRegClass::Avr(_) => unimplemented!(),
-        InlineAsmRegClass::Bpf(_) => unimplemented!(),
-        InlineAsmRegClass::Hexagon(_) => unimplemented!(),
-        InlineAsmRegClass::Mips(_) => unimplemented!(),
-        InlineAsmRegClass::Msp430(_) => unimplemented!(),
-        InlineAsmRegClass::Nvptx(_) => unimplemented!(),
-        InlineAsmRegClass::PowerPC(_) => unimplemented!(),
+        | InlineAsmRegClass::Arm(ArmInlineAsmRegClass::qreg_low4) => match modifier {
+            // NOTE: `e` and `f` select the low and high d register of the q register.
+            None => Some('q'),
+            Some('e') => Some('e'),
+            Some('f') => Some('f'),
+            _ => unreachable!(),
+        },
+        InlineAsmRegClass::Avr(_) => unreachable!("unsupported register class"),
+        InlineAsmRegClass::Bpf(_) => unreachable!("unsupported register class"),
+        InlineAsmRegClass::Hexagon(_) => None,
+        InlineAsmRegClass::Mips(_) => None,
+        InlineAsmRegClass::Msp430(_) => unreachable!("unsupported register class"),
+        // NOTE: PTX operands are virtual registers that GCC prints with their `%r` prefix, so there's
+        // nothing to select with a modifier.
+        InlineAsmRegClass::Nvptx(_) => None,
+        InlineAsmRegClass::PowerPC(PowerPCInlineAsmRegClass::reg)
+        | InlineAsmRegClass::PowerPC(PowerPCInlineAsmRegClass::reg_nonzero)
+        | InlineAsmRegClass::PowerPC(PowerPCInlineAsmRegClass::freg) => None,
+        InlineAsmRegClass::PowerPC(PowerPCInlineAsmRegClass::cr)
+        | InlineAsmRegClass::PowerPC(PowerPCInlineAsmRegClass::xer) => {
+            unreachable!("clobber-only")
+        },
         InlineAsmRegClass::RiscV(RiscVInlineAsmRegClass::reg)
-        | InlineAsmRegClass::RiscV(RiscVInlineAsmRegClass::freg) => unimplemented!(),
-        InlineAsmRegClass::RiscV(RiscVInlineAsmRegClass::vreg) => unimplemented!(),
+        | InlineAsmRegClass::RiscV(RiscVInlineAsmRegClass::freg) => None,
+        InlineAsmRegClass::RiscV(RiscVInlineAsmRegClass::vreg) => unreachable!("clobber-only"),
         InlineAsmRegClass::X86(X86InlineAsmRegClass::reg)
         | InlineAsmRegClass::X86(X86InlineAsmRegClass::reg_abcd) => match modifier {
             None => if arch == InlineAsmArch::X86_64 { Some('q') } else { Some('k') },
@@ -807,12 +1129,14 @@ fn modifier_to_gcc(arch: InlineAsmArch, reg: InlineAsmRegClass, modifier: Option
         InlineAsmRegClass::X86(X86InlineAsmRegClass::x87_reg | X86InlineAsmRegClass::mmx_reg | X86InlineAsmRegClass::tmm_reg) => {
             unreachable!("clobber-only")
         }
-        InlineAsmRegClass::Wasm(WasmInlineAsmRegClass::local) => unimplemented!(),
+        InlineAsmRegClass::Wasm(WasmInlineAsmRegClass::local) => {
+            bug!("GCC backend does not support inline asm on wasm")
+        },
         InlineAsmRegClass::SpirV(SpirVInlineAsmRegClass::reg) => {
             bug!("LLVM backend does not support SPIR-V")
         },
-        InlineAsmRegClass::S390x(S390xInlineAsmRegClass::reg) => unimplemented!(),
-        InlineAsmRegClass::S390x(S390xInlineAsmRegClass::freg) => unimplemented!(),
+        InlineAsmRegClass::S390x(S390xInlineAsmRegClass::reg)
+        | InlineAsmRegClass::S390x(S390xInlineAsmRegClass::freg) => None,
         InlineAsmRegClass::Err => unreachable!(),
     }
 }
@@ -1,4 +1,5 @@
 use std::{env, fs};
+use std::path::Path;
 
 use gccjit::OutputKind;
 use rustc_codegen_ssa::{CompiledModule, ModuleCodegen};
@@ -6,11 +7,11 @@
 use rustc_errors::Handler;
 use rustc_session::config::OutputType;
 use rustc_span::fatal_error::FatalError;
-use rustc_target::spec::SplitDebuginfo;
 
 use crate::{GccCodegenBackend, GccContext};
+use crate::errors::{DumpReproducerFailed, NonUtf8Path};
 
-pub(crate) unsafe fn codegen(cgcx: &CodegenContext<GccCodegenBackend>, _diag_handler: &Handler, module: ModuleCodegen<GccContext>, config: &ModuleConfig) -> Result<CompiledModule, FatalError> {
+pub(crate) unsafe fn codegen(cgcx: &CodegenContext<GccCodegenBackend>, diag_handler: &Handler, module: ModuleCodegen<GccContext>, config: &ModuleConfig) -> Result<CompiledModule, FatalError> {
     let _timer = cgcx.prof.generic_activity_with_arg("LLVM_module_codegen", &*module.name);
     {
         let context = &module.module_llvm.context;
@@ -34,7 +35,7 @@ pub(crate) unsafe fn codegen(cgcx: &CodegenContext<GccCodegenBackend>, _diag_han
                 .prof
                 .generic_activity_with_arg("LLVM_module_codegen_emit_asm", &*module.name);
             let path = cgcx.output_filenames.temp_path(OutputType::Assembly, module_name);
-            context.compile_to_file(OutputKind::Assembler, path.to_str().expect("path to str"));
+            context.compile_to_file(OutputKind::Assembler, path_to_str(diag_handler, &path)?);
         }
 
         match config.emit_obj {
@@ -54,12 +55,25 @@ pub(crate) unsafe fn codegen(cgcx: &CodegenContext<GccCodegenBackend>, _diag_han
                     context.dump_reproducer_to_file(&format!("/tmp/reproducers/{}.c", module.name));
                     println!("Dumped reproducer {}", module.name);
                 }
+                if let Some(dir) = &cgcx.opts.unstable_opts.dump_gcc_reproducer {
+                    match fs::create_dir_all(dir) {
+                        Ok(()) => {
+                            let path = dir.join(format!("{}.c", module.name));
+                            if let Ok(path) = path_to_str(diag_handler, &path) {
+                                context.dump_reproducer_to_file(path);
+                            }
+                        },
+                        Err(error) => {
+                            diag_handler.emit_err(DumpReproducerFailed { path: dir, error });
+                        },
+                    }
+                }
                 if env::var("CG_GCCJIT_DUMP_TO_FILE").as_deref() == Ok("1") {
                     let _ = fs::create_dir("/tmp/gccjit_dumps");
                     let path = &format!("/tmp/gccjit_dumps/{}.c", module.name);
                     context.dump_to_file(path, true);
                 }
-                context.compile_to_file(OutputKind::ObjectFile, obj_out.to_str().expect("path to str"));
+                context.compile_to_file(OutputKind::ObjectFile, path_to_str(diag_handler, &obj_out)?);
             }
 
             EmitObj::Bitcode => {
@@ -70,14 +84,29 @@ pub(crate) unsafe fn codegen(cgcx: &CodegenContext<GccCodegenBackend>, _diag_han
         }
     }
 
+    // NOTE: libgccjit compiles the module in its own temporary directory, so -gsplit-dwarf would
+    // write the DWARF object there and it would be deleted with it. Until this is supported, the
+    // debuginfo is kept in the object file and no DWARF object is emitted: a warning is emitted in
+    // GccCodegenBackend::init when split debuginfo is requested.
+    // TODO: support -Csplit-debuginfo.
+    let emit_dwarf_obj = false;
+
     Ok(module.into_compiled_module(
         config.emit_obj != EmitObj::None,
-        cgcx.target_can_use_split_dwarf && cgcx.split_debuginfo == SplitDebuginfo::Unpacked,
+        emit_dwarf_obj,
         config.emit_bc,
         &cgcx.output_filenames,
     ))
 }
 
+/// libgccjit only accepts UTF-8 paths, so this emits an error for the other ones.
+fn path_to_str<'a>(diag_handler: &Handler, path: &'a Path) -> Result<&'a str, FatalError> {
+    path.to_str().ok_or_else(|| {
+        diag_handler.emit_err(NonUtf8Path { path });
+        FatalError
+    })
+}
+
 pub(crate) fn link(_cgcx: &CodegenContext<GccCodegenBackend>, _diag_handler: &Handler, mut _modules: Vec<ModuleCodegen<GccContext>>) -> Result<ModuleCodegen<GccContext>, FatalError> {
     unimplemented!();
 }
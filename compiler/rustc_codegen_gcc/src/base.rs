@@ -15,6 +15,7 @@
 use rustc_codegen_ssa::traits::DebugInfoMethods;
 use rustc_session::config::DebugInfo;
 use rustc_span::Symbol;
+use rustc_target::spec::SanitizerSet;
 
 use crate::GccContext;
 use crate::builder::Builder;
@@ -76,18 +77,44 @@ fn module_codegen(tcx: TyCtxt<'_>, (cgu_name, supports_128bit_integers): (Symbol
         // Instantiate monomorphizations without filling out definitions yet...
         //let llvm_module = ModuleLlvm::new(tcx, &cgu_name.as_str());
         let context = Context::default();
-        // TODO(antoyo): only set on x86 platforms.
-        context.add_command_line_option("-masm=intel");
-        // TODO(antoyo): only add the following cli argument if the feature is supported.
-        context.add_command_line_option("-msse2");
-        context.add_command_line_option("-mavx2");
-        context.add_command_line_option("-msha");
-        context.add_command_line_option("-mpclmul");
-        // FIXME(antoyo): the following causes an illegal instruction on vmovdqu64 in std_example on my CPU.
-        // Only add if the CPU supports it.
-        //context.add_command_line_option("-mavx512f");
+        // NOTE: these options are only recognized by GCC when it targets x86, so passing them to
+        // a GCC targeting another architecture makes it error out.
+        if matches!(&*tcx.sess.target.arch, "x86" | "x86_64") {
+            context.add_command_line_option("-masm=intel");
+            // TODO(antoyo): only add the following cli argument if the feature is supported.
+            context.add_command_line_option("-msse2");
+            context.add_command_line_option("-mavx2");
+            context.add_command_line_option("-msha");
+            context.add_command_line_option("-mpclmul");
+            // FIXME(antoyo): the following causes an illegal instruction on vmovdqu64 in std_example on my CPU.
+            // Only add if the CPU supports it.
+            //context.add_command_line_option("-mavx512f");
+
+            // NOTE: without -mcx16, GCC lowers the 16-byte __atomic_* builtins to libatomic calls
+            // instead of cmpxchg16b-based sequences.
+            let target = &tcx.sess.target;
+            let has_cmpxchg16b = target.max_atomic_width() >= 128
+                || target.features.split(',').chain(tcx.sess.opts.cg.target_feature.split(','))
+                    .any(|feature| feature == "+cmpxchg16b");
+            if target.arch == "x86_64" && has_cmpxchg16b {
+                context.add_command_line_option("-mcx16");
+            }
+        }
+        let asan_enabled = tcx.sess.opts.unstable_opts.sanitizer.intersects(SanitizerSet::ADDRESS | SanitizerSet::KERNELADDRESS);
         for arg in &tcx.sess.opts.cg.llvm_args {
-            context.add_command_line_option(arg);
+            // NOTE: the Linux kernel tunes KASAN with the LLVM spelling of the ASan options, so
+            // translate them to their GCC equivalent when ASan is enabled.
+            match arg.strip_prefix("-asan-") {
+                Some(param) if asan_enabled => {
+                    if let Some(offset) = param.strip_prefix("mapping-offset=") {
+                        context.add_command_line_option(format!("-fasan-shadow-offset={}", offset));
+                    }
+                    else {
+                        context.add_command_line_option(format!("--param=asan-{}", param));
+                    }
+                },
+                _ => context.add_command_line_option(arg),
+            }
         }
         // NOTE: This is needed to compile the file src/intrinsic/archs.rs during a bootstrap of rustc.
         context.add_command_line_option("-fno-var-tracking-assignments");
@@ -95,19 +122,43 @@ fn module_codegen(tcx: TyCtxt<'_>, (cgu_name, supports_128bit_integers): (Symbol
         context.add_command_line_option("-fno-semantic-interposition");
         // NOTE: Rust relies on LLVM not doing TBAA (https://github.com/rust-lang/unsafe-code-guidelines/issues/292).
         context.add_command_line_option("-fno-strict-aliasing");
+        // NOTE: Rust relies on LLVM doing wrapping on overflow, for instance in the relative
+        // discriminant computed for niche-filling enums. The unchecked signed operations assume
+        // explicitly that they don't overflow since this makes it defined.
+        context.add_command_line_option("-fwrapv");
 
         if tcx.sess.opts.unstable_opts.function_sections.unwrap_or(tcx.sess.target.function_sections) {
             context.add_command_line_option("-ffunction-sections");
             context.add_command_line_option("-fdata-sections");
         }
 
+        if tcx.sess.opts.unstable_opts.sanitizer.contains(SanitizerSet::SHADOWCALLSTACK) {
+            context.add_command_line_option("-fsanitize=shadow-call-stack");
+            // NOTE: the shadow call stack pointer lives in x18, so GCC must not allocate it.
+            context.add_command_line_option("-ffixed-x18");
+        }
+        if tcx.sess.opts.unstable_opts.sanitizer.contains(SanitizerSet::KERNELADDRESS) {
+            context.add_command_line_option("-fsanitize=kernel-address");
+            if tcx.sess.opts.unstable_opts.sanitizer_recover.contains(SanitizerSet::KERNELADDRESS) {
+                context.add_command_line_option("-fsanitize-recover=kernel-address");
+            }
+        }
+
         if env::var("CG_GCCJIT_DUMP_CODE").as_deref() == Ok("1") {
             context.set_dump_code_on_compile(true);
         }
         if env::var("CG_GCCJIT_DUMP_GIMPLE").as_deref() == Ok("1") {
             context.set_dump_initial_gimple(true);
         }
-        context.set_debug_info(true);
+        match tcx.sess.opts.debuginfo {
+            DebugInfo::None => {},
+            DebugInfo::Limited => {
+                context.set_debug_info(true);
+                // NOTE: like LLVM's limited debuginfo, only keep the line tables and the functions.
+                context.add_command_line_option("-g1");
+            },
+            DebugInfo::Full => context.set_debug_info(true),
+        }
         if env::var("CG_GCCJIT_DUMP_EVERYTHING").as_deref() == Ok("1") {
             context.set_dump_everything(true);
         }
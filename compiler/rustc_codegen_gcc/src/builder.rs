@@ -9,6 +9,7 @@
     ComparisonOp,
     Context,
     Function,
+    Location,
     LValue,
     RValue,
     ToRValue,
@@ -37,6 +38,7 @@
 use rustc_middle::bug;
 use rustc_middle::ty::{ParamEnv, Ty, TyCtxt};
 use rustc_middle::ty::layout::{FnAbiError, FnAbiOfHelpers, FnAbiRequest, HasParamEnv, HasTyCtxt, LayoutError, LayoutOfHelpers, TyAndLayout};
+use rustc_session::config::OptLevel;
 use rustc_span::Span;
 use rustc_span::def_id::DefId;
 use rustc_target::abi::{
@@ -70,6 +72,9 @@ pub struct Builder<'a: 'gcc, 'gcc, 'tcx> {
     pub cx: &'a CodegenCx<'gcc, 'tcx>,
     pub block: Block<'gcc>,
     stack_var_count: Cell<usize>,
+    /// The source location set by rustc_codegen_ssa for the statement being lowered, used for the
+    /// line-number debuginfo.
+    pub location: Option<Location<'gcc>>,
 }
 
 impl<'a, 'gcc, 'tcx> Builder<'a, 'gcc, 'tcx> {
@@ -78,11 +83,13 @@ fn with_cx(cx: &'a CodegenCx<'gcc, 'tcx>, block: Block<'gcc>) -> Self {
             cx,
             block,
             stack_var_count: Cell::new(0),
+            location: None,
         }
     }
 
-    fn atomic_extremum(&mut self, operation: ExtremumOperation, dst: RValue<'gcc>, src: RValue<'gcc>, order: AtomicOrdering) -> RValue<'gcc> {
-        let size = src.get_type().get_size();
+    fn atomic_extremum(&mut self, operation: ExtremumOperation, dst: RValue<'gcc>, src: RValue<'gcc>, order: AtomicOrdering, signed: bool) -> RValue<'gcc> {
+        let src_type = src.get_type();
+        let size = src_type.get_size();
 
         let func = self.current_func();
 
@@ -92,15 +99,16 @@ fn atomic_extremum(&mut self, operation: ExtremumOperation, dst: RValue<'gcc>, s
                 AtomicOrdering::AcquireRelease | AtomicOrdering::Release => AtomicOrdering::Acquire,
                 _ => order,
             };
+        // NOTE: the __atomic_load_N builtins return an unsigned integer, so cast the value back to
+        // the type of src.
         let previous_value = self.atomic_load(dst.get_type(), dst, load_ordering, Size::from_bytes(size));
-        let previous_var = func.new_local(None, previous_value.get_type(), "previous_value");
-        let return_value = func.new_local(None, previous_value.get_type(), "return_value");
-        self.llbb().add_assignment(None, previous_var, previous_value);
-        self.llbb().add_assignment(None, return_value, previous_var.to_rvalue());
+        let previous_value = self.context.new_cast(self.location, previous_value, src_type);
+        let previous_var = func.new_local(self.location, src_type, "previous_value");
+        self.llbb().add_assignment(self.location, previous_var, previous_value);
 
         let while_block = func.new_block("while");
         let after_block = func.new_block("after_while");
-        self.llbb().end_with_jump(None, while_block);
+        self.llbb().end_with_jump(self.location, while_block);
 
         // NOTE: since jumps were added and compare_exchange doesn't expect this, the current block in the
         // state need to be updated.
@@ -112,40 +120,98 @@ fn atomic_extremum(&mut self, operation: ExtremumOperation, dst: RValue<'gcc>, s
                 ExtremumOperation::Min => ComparisonOp::GreaterThan,
             };
 
-        let cond1 = self.context.new_comparison(None, comparison_operator, previous_var.to_rvalue(), self.context.new_cast(None, src, previous_value.get_type()));
+        // NOTE: the comparison must be done with the signedness of the operation, regardless of
+        // the signedness of the type of the values.
+        let comparison_type =
+            if signed {
+                src_type.to_signed(&self.cx)
+            }
+            else {
+                src_type.to_unsigned(&self.cx)
+            };
+        let previous = self.context.new_cast(self.location, previous_var.to_rvalue(), comparison_type);
+        let new_value = self.context.new_cast(self.location, src, comparison_type);
+        let cond1 = self.context.new_comparison(self.location, comparison_operator, previous, new_value);
+        // NOTE: on failure, compare_exchange updates previous_var with the current value.
         let compare_exchange = self.compare_exchange(dst, previous_var, src, order, load_ordering, false);
-        let cond2 = self.cx.context.new_unary_op(None, UnaryOp::LogicalNegate, compare_exchange.get_type(), compare_exchange);
-        let cond = self.cx.context.new_binary_op(None, BinaryOp::LogicalAnd, self.cx.bool_type, cond1, cond2);
+        let cond2 = self.cx.context.new_unary_op(self.location, UnaryOp::LogicalNegate, compare_exchange.get_type(), compare_exchange);
+        let cond = self.cx.context.new_binary_op(self.location, BinaryOp::LogicalAnd, self.cx.bool_type, cond1, cond2);
 
-        while_block.end_with_conditional(None, cond, while_block, after_block);
+        while_block.end_with_conditional(self.location, cond, while_block, after_block);
 
         // NOTE: since jumps were added in a place rustc does not expect, the current block in the
         // state need to be updated.
         self.switch_to_block(after_block);
 
-        return_value.to_rvalue()
+        // NOTE: previous_var now contains the value that was replaced, or the current value if
+        // it did not need to be replaced.
+        previous_var.to_rvalue()
     }
 
     fn compare_exchange(&self, dst: RValue<'gcc>, cmp: LValue<'gcc>, src: RValue<'gcc>, order: AtomicOrdering, failure_order: AtomicOrdering, weak: bool) -> RValue<'gcc> {
         let size = src.get_type().get_size();
         let compare_exchange = self.context.get_builtin_function(&format!("__atomic_compare_exchange_{}", size));
+        // NOTE: GCC requires the success ordering to be at least as strong as the failure ordering
+        // and otherwise upgrades it to SeqCst, while Rust allows a stronger failure ordering.
+        // Only strengthen the success ordering as much as needed.
+        let order =
+            match (order, failure_order) {
+                (AtomicOrdering::Unordered | AtomicOrdering::Relaxed, AtomicOrdering::Acquire) => AtomicOrdering::Acquire,
+                (AtomicOrdering::Release, AtomicOrdering::Acquire) => AtomicOrdering::AcquireRelease,
+                (_, AtomicOrdering::SequentiallyConsistent) => AtomicOrdering::SequentiallyConsistent,
+                _ => order,
+            };
         let order = self.context.new_rvalue_from_int(self.i32_type, order.to_gcc());
         let failure_order = self.context.new_rvalue_from_int(self.i32_type, failure_order.to_gcc());
         let weak = self.context.new_rvalue_from_int(self.bool_type, weak as i32);
 
         let void_ptr_type = self.context.new_type::<*mut ()>();
         let volatile_void_ptr_type = void_ptr_type.make_volatile();
-        let dst = self.context.new_cast(None, dst, volatile_void_ptr_type);
-        let expected = self.context.new_cast(None, cmp.get_address(None), void_ptr_type);
+        let dst = self.context.new_cast(self.location, dst, volatile_void_ptr_type);
+        let expected = self.context.new_cast(self.location, cmp.get_address(self.location), void_ptr_type);
 
         // NOTE: not sure why, but we have the wrong type here.
         let int_type = compare_exchange.get_param(2).to_rvalue().get_type();
-        let src = self.context.new_cast(None, src, int_type);
-        self.context.new_call(None, compare_exchange, &[dst, expected, src, weak, order, failure_order])
+        let src = self.context.new_cast(self.location, src, int_type);
+        self.context.new_call(self.location, compare_exchange, &[dst, expected, src, weak, order, failure_order])
+    }
+
+    /// Tell GCC that `cond` is always true by making the other branch unreachable.
+    pub fn assume_condition(&mut self, cond: RValue<'gcc>) {
+        let func = self.current_func();
+        let unreachable_block = func.new_block("assumption_violated");
+        let after_block = func.new_block("after_assumption");
+        self.llbb().end_with_conditional(self.location, cond, after_block, unreachable_block);
+
+        let unreachable = self.context.get_builtin_function("__builtin_unreachable");
+        unreachable_block.add_eval(self.location, self.context.new_call(self.location, unreachable, &[]));
+        unreachable_block.end_with_jump(self.location, after_block);
+
+        // NOTE: since jumps were added in a place rustc does not expect, the current block in the
+        // state need to be updated.
+        self.switch_to_block(after_block);
+    }
+
+    /// Compute a signed operation with the type-generic overflow builtin `builtin_name` and tell
+    /// GCC that it does not overflow: since -fwrapv makes signed overflow defined, the C operators
+    /// no longer convey this.
+    fn unchecked_signed_binop(&mut self, builtin_name: &str, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
+        // NOTE: rustc_codegen_ssa also uses the signed operations on unsigned types (e.g. for the
+        // size of unsized values), so compute the result in the signed type.
+        let typ = a.get_type();
+        let signed_type = typ.to_signed(self);
+        let a = self.gcc_int_cast(a, signed_type);
+        let b = self.gcc_int_cast(b, signed_type);
+        let result = self.current_func().new_local(self.location, signed_type, "unchecked_result");
+        let overflow_func = self.context.get_builtin_function(builtin_name);
+        let overflow = self.overflow_call(overflow_func, &[a, b, result.get_address(self.location)], None);
+        let no_overflow = self.context.new_unary_op(self.location, UnaryOp::LogicalNegate, overflow.get_type(), overflow);
+        self.assume_condition(no_overflow);
+        self.gcc_int_cast(result.to_rvalue(), typ)
     }
 
     pub fn assign(&self, lvalue: LValue<'gcc>, value: RValue<'gcc>) {
-        self.llbb().add_assignment(None, lvalue, value);
+        self.llbb().add_assignment(self.location, lvalue, value);
     }
 
     fn check_call<'b>(&mut self, _typ: &str, func: Function<'gcc>, args: &'b [RValue<'gcc>]) -> Cow<'b, [RValue<'gcc>]> {
@@ -165,14 +231,30 @@ fn check_call<'b>(&mut self, _typ: &str, func: Function<'gcc>, args: &'b [RValue
             return Cow::Borrowed(args);
         }
 
+        let mut on_stack_param_indices = FxHashSet::default();
+        if let Some(indices) = self.on_stack_function_params.borrow().get(&func) {
+            on_stack_param_indices = indices.clone();
+        }
+
         let casted_args: Vec<_> = param_types
             .into_iter()
             .zip(args.iter())
             .enumerate()
-            .map(|(_i, (expected_ty, &actual_val))| {
+            .map(|(index, (expected_ty, &actual_val))| {
                 let actual_ty = actual_val.get_type();
                 if expected_ty != actual_ty {
-                    self.bitcast(actual_val, expected_ty)
+                    if on_stack_param_indices.contains(&index) {
+                        // NOTE: the caller passes the address of its copy of a byval argument, but
+                        // GCC expects the value itself, which it copies to the stack.
+                        actual_val.dereference(self.location).to_rvalue()
+                    }
+                    else if expected_ty.is_bool() != actual_ty.is_bool() && !actual_ty.is_vector() && !expected_ty.is_vector() {
+                        // NOTE: see the same conversion in check_ptr_call.
+                        self.context.new_cast(self.location, actual_val, expected_ty)
+                    }
+                    else {
+                        self.bitcast(actual_val, expected_ty)
+                    }
                 }
                 else {
                     actual_val
@@ -218,10 +300,15 @@ fn check_ptr_call<'b>(&mut self, _typ: &str, func_ptr: RValue<'gcc>, args: &'b [
                 let actual_ty = actual_val.get_type();
                 if expected_ty != actual_ty {
                     if !actual_ty.is_vector() && !expected_ty.is_vector() && actual_ty.is_integral() && expected_ty.is_integral() && actual_ty.get_size() != expected_ty.get_size() {
-                        self.context.new_cast(None, actual_val, expected_ty)
+                        self.context.new_cast(self.location, actual_val, expected_ty)
+                    }
+                    else if expected_ty.is_bool() != actual_ty.is_bool() && !actual_ty.is_vector() && !expected_ty.is_vector() {
+                        // NOTE: a bitcast to or from a bool would not materialize it as 0 or 1 like
+                        // the C ABI expects, so convert the value instead.
+                        self.context.new_cast(self.location, actual_val, expected_ty)
                     }
                     else if on_stack_param_indices.contains(&index) {
-                        actual_val.dereference(None).to_rvalue()
+                        actual_val.dereference(self.location).to_rvalue()
                     }
                     else {
                         assert!(!((actual_ty.is_vector() && !expected_ty.is_vector()) || (!actual_ty.is_vector() && expected_ty.is_vector())), "{:?} ({}) -> {:?} ({}), index: {:?}[{}]", actual_ty, actual_ty.is_vector(), expected_ty, expected_ty.is_vector(), func_ptr, index);
@@ -258,6 +345,7 @@ pub fn current_func(&self) -> Function<'gcc> {
     fn function_call(&mut self, func: RValue<'gcc>, args: &[RValue<'gcc>], _funclet: Option<&Funclet>) -> RValue<'gcc> {
         // TODO(antoyo): remove when the API supports a different type for functions.
         let func: Function<'gcc> = self.cx.rvalue_as_function(func);
+        self.cx.check_calling_convention(func);
         let args = self.check_call("call", func, args);
 
         // gccjit requires to use the result of functions, even when it's not used.
@@ -267,12 +355,12 @@ fn function_call(&mut self, func: RValue<'gcc>, args: &[RValue<'gcc>], _funclet:
         let current_func = self.block.get_function();
         if return_type != void_type {
             unsafe { RETURN_VALUE_COUNT += 1 };
-            let result = current_func.new_local(None, return_type, &format!("returnValue{}", unsafe { RETURN_VALUE_COUNT }));
-            self.block.add_assignment(None, result, self.cx.context.new_call(None, func, &args));
+            let result = current_func.new_local(self.location, return_type, &format!("returnValue{}", unsafe { RETURN_VALUE_COUNT }));
+            self.block.add_assignment(self.location, result, self.cx.context.new_call(self.location, func, &args));
             result.to_rvalue()
         }
         else {
-            self.block.add_eval(None, self.cx.context.new_call(None, func, &args));
+            self.block.add_eval(self.location, self.cx.context.new_call(self.location, func, &args));
             // Return dummy value when not having return value.
             self.context.new_rvalue_from_long(self.isize_type, 0)
         }
@@ -290,26 +378,28 @@ fn function_ptr_call(&mut self, func_ptr: RValue<'gcc>, args: &[RValue<'gcc>], _
 
         if return_type != void_type {
             unsafe { RETURN_VALUE_COUNT += 1 };
-            let result = current_func.new_local(None, return_type, &format!("ptrReturnValue{}", unsafe { RETURN_VALUE_COUNT }));
             let func_name = format!("{:?}", func_ptr);
             let args = llvm::adjust_intrinsic_arguments(&self, gcc_func, args, &func_name);
-            self.block.add_assignment(None, result, self.cx.context.new_call_through_ptr(None, func_ptr, &args));
+            let return_value = self.cx.context.new_call_through_ptr(self.location, func_ptr, &args);
+            let return_value = llvm::adjust_intrinsic_return_value(&self, return_value, &func_name, &args);
+            let result = current_func.new_local(self.location, return_value.get_type(), &format!("ptrReturnValue{}", unsafe { RETURN_VALUE_COUNT }));
+            self.block.add_assignment(self.location, result, return_value);
             result.to_rvalue()
         }
         else {
             #[cfg(not(feature="master"))]
             if gcc_func.get_param_count() == 0 {
                 // FIXME(antoyo): As a temporary workaround for unsupported LLVM intrinsics.
-                self.block.add_eval(None, self.cx.context.new_call_through_ptr(None, func_ptr, &[]));
+                self.block.add_eval(self.location, self.cx.context.new_call_through_ptr(self.location, func_ptr, &[]));
             }
             else {
-                self.block.add_eval(None, self.cx.context.new_call_through_ptr(None, func_ptr, &args));
+                self.block.add_eval(self.location, self.cx.context.new_call_through_ptr(self.location, func_ptr, &args));
             }
             #[cfg(feature="master")]
-            self.block.add_eval(None, self.cx.context.new_call_through_ptr(None, func_ptr, &args));
+            self.block.add_eval(self.location, self.cx.context.new_call_through_ptr(self.location, func_ptr, &args));
             // Return dummy value when not having return value.
-            let result = current_func.new_local(None, self.isize_type, "dummyValueThatShouldNeverBeUsed");
-            self.block.add_assignment(None, result, self.context.new_rvalue_from_long(self.isize_type, 0));
+            let result = current_func.new_local(self.location, self.isize_type, "dummyValueThatShouldNeverBeUsed");
+            self.block.add_assignment(self.location, result, self.context.new_rvalue_from_long(self.isize_type, 0));
             result.to_rvalue()
         }
     }
@@ -321,8 +411,8 @@ pub fn overflow_call(&self, func: Function<'gcc>, args: &[RValue<'gcc>], _funcle
         let current_func = self.block.get_function();
         // TODO(antoyo): return the new_call() directly? Since the overflow function has no side-effects.
         unsafe { RETURN_VALUE_COUNT += 1 };
-        let result = current_func.new_local(None, return_type, &format!("overflowReturnValue{}", unsafe { RETURN_VALUE_COUNT }));
-        self.block.add_assignment(None, result, self.cx.context.new_call(None, func, &args));
+        let result = current_func.new_local(self.location, return_type, &format!("overflowReturnValue{}", unsafe { RETURN_VALUE_COUNT }));
+        self.block.add_assignment(self.location, result, self.cx.context.new_call(self.location, func, &args));
         result.to_rvalue()
     }
 }
@@ -410,7 +500,7 @@ fn switch_to_block(&mut self, block: Self::BasicBlock) {
     }
 
     fn ret_void(&mut self) {
-        self.llbb().end_with_void_return(None)
+        self.llbb().end_with_void_return(self.location)
     }
 
     fn ret(&mut self, value: RValue<'gcc>) {
@@ -418,20 +508,20 @@ fn ret(&mut self, value: RValue<'gcc>) {
             if self.structs_as_pointer.borrow().contains(&value) {
                 // NOTE: hack to workaround a limitation of the rustc API: see comment on
                 // CodegenCx.structs_as_pointer
-                value.dereference(None).to_rvalue()
+                value.dereference(self.location).to_rvalue()
             }
             else {
                 value
             };
-        self.llbb().end_with_return(None, value);
+        self.llbb().end_with_return(self.location, value);
     }
 
     fn br(&mut self, dest: Block<'gcc>) {
-        self.llbb().end_with_jump(None, dest)
+        self.llbb().end_with_jump(self.location, dest)
     }
 
     fn cond_br(&mut self, cond: RValue<'gcc>, then_block: Block<'gcc>, else_block: Block<'gcc>) {
-        self.llbb().end_with_conditional(None, cond, then_block, else_block)
+        self.llbb().end_with_conditional(self.location, cond, then_block, else_block)
     }
 
     fn switch(&mut self, value: RValue<'gcc>, default_block: Block<'gcc>, cases: impl ExactSizeIterator<Item = (u128, Block<'gcc>)>) {
@@ -441,29 +531,39 @@ fn switch(&mut self, value: RValue<'gcc>, default_block: Block<'gcc>, cases: imp
             let on_val = self.const_uint_big(typ, on_val);
             gcc_cases.push(self.context.new_case(on_val, on_val, dest));
         }
-        self.block.end_with_switch(None, value, default_block, &gcc_cases);
+        self.block.end_with_switch(self.location, value, default_block, &gcc_cases);
     }
 
     fn invoke(&mut self, typ: Type<'gcc>, func: RValue<'gcc>, args: &[RValue<'gcc>], then: Block<'gcc>, catch: Block<'gcc>, _funclet: Option<&Funclet>) -> RValue<'gcc> {
         // TODO(bjorn3): Properly implement unwinding.
         let call_site = self.call(typ, func, args, None);
         let condition = self.context.new_rvalue_from_int(self.bool_type, 1);
-        self.llbb().end_with_conditional(None, condition, then, catch);
+        self.llbb().end_with_conditional(self.location, condition, then, catch);
         call_site
     }
 
     fn unreachable(&mut self) {
-        let func = self.context.get_builtin_function("__builtin_unreachable");
-        self.block.add_eval(None, self.context.new_call(None, func, &[]));
+        // NOTE: unlike LLVM's TrapUnreachable, a call to __builtin_trap is not treated as
+        // undefined behavior by GCC, so only emit it when explicitly requested with
+        // -Z trap-unreachable=yes to keep the optimizations enabled by __builtin_unreachable.
+        let builtin =
+            if self.tcx.sess.opts.unstable_opts.trap_unreachable == Some(true) {
+                "__builtin_trap"
+            }
+            else {
+                "__builtin_unreachable"
+            };
+        let func = self.context.get_builtin_function(builtin);
+        self.block.add_eval(self.location, self.context.new_call(self.location, func, &[]));
         let return_type = self.block.get_function().get_return_type();
         let void_type = self.context.new_type::<()>();
         if return_type == void_type {
-            self.block.end_with_void_return(None)
+            self.block.end_with_void_return(self.location)
         }
         else {
             let return_value = self.current_func()
-                .new_local(None, return_type, "unreachableReturn");
-            self.block.end_with_return(None, return_value)
+                .new_local(self.location, return_type, "unreachableReturn");
+            self.block.end_with_return(self.location, return_value)
         }
     }
 
@@ -496,11 +596,17 @@ fn udiv(&mut self, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
     }
 
     fn exactudiv(&mut self, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
-        // TODO(antoyo): poison if not exact.
         let a_type = a.get_type().to_unsigned(self);
         let a = self.gcc_int_cast(a, a_type);
         let b_type = b.get_type().to_unsigned(self);
         let b = self.gcc_int_cast(b, b_type);
+        // NOTE: the hint costs an extra division when GCC doesn't optimize it away.
+        if self.sess().opts.optimize != OptLevel::No && self.is_native_int_type(a_type) {
+            // NOTE: let GCC know that the division is exact.
+            let remainder = self.context.new_binary_op(self.location, BinaryOp::Modulo, a_type, a, b);
+            let is_exact = self.context.new_comparison(self.location, ComparisonOp::Equals, remainder, self.context.new_rvalue_zero(a_type));
+            self.assume_condition(is_exact);
+        }
         a / b
     }
 
@@ -509,11 +615,16 @@ fn sdiv(&mut self, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
     }
 
     fn exactsdiv(&mut self, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
-        // TODO(antoyo): poison if not exact.
         // FIXME(antoyo): rustc_codegen_ssa::mir::intrinsic uses different types for a and b but they
         // should be the same.
         let typ = a.get_type().to_signed(self);
-        let b = self.context.new_cast(None, b, typ);
+        let b = self.context.new_cast(self.location, b, typ);
+        if self.sess().opts.optimize != OptLevel::No && self.is_native_int_type(typ) {
+            // NOTE: let GCC know that the division is exact.
+            let remainder = self.context.new_binary_op(self.location, BinaryOp::Modulo, typ, a, b);
+            let is_exact = self.context.new_comparison(self.location, ComparisonOp::Equals, remainder, self.context.new_rvalue_zero(typ));
+            self.assume_condition(is_exact);
+        }
         a / b
     }
 
@@ -533,12 +644,12 @@ fn frem(&mut self, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
         if a.get_type().is_compatible_with(self.cx.float_type) {
             let fmodf = self.context.get_builtin_function("fmodf");
             // FIXME(antoyo): this seems to produce the wrong result.
-            return self.context.new_call(None, fmodf, &[a, b]);
+            return self.context.new_call(self.location, fmodf, &[a, b]);
         }
         assert_eq!(a.get_type().unqualified(), self.cx.double_type);
 
         let fmod = self.context.get_builtin_function("fmod");
-        return self.context.new_call(None, fmod, &[a, b]);
+        return self.context.new_call(self.location, fmod, &[a, b]);
     }
 
     fn shl(&mut self, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
@@ -546,13 +657,25 @@ fn shl(&mut self, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
     }
 
     fn lshr(&mut self, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
-        self.gcc_lshr(a, b)
+        // NOTE: GCC does a logical shift only when the value is unsigned.
+        let a_type = a.get_type();
+        if a_type.is_vector() {
+            return self.gcc_lshr(a, b);
+        }
+        let a = self.gcc_int_cast(a, a_type.to_unsigned(self));
+        let result = self.gcc_lshr(a, b);
+        self.gcc_int_cast(result, a_type)
     }
 
     fn ashr(&mut self, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
-        // TODO(antoyo): check whether behavior is an arithmetic shift for >> .
-        // It seems to be if the value is signed.
-        self.gcc_lshr(a, b)
+        // NOTE: GCC does an arithmetic shift only when the value is signed.
+        let a_type = a.get_type();
+        if a_type.is_vector() {
+            return self.gcc_lshr(a, b);
+        }
+        let a = self.gcc_int_cast(a, a_type.to_signed(self));
+        let result = self.gcc_lshr(a, b);
+        self.gcc_int_cast(result, a_type)
     }
 
     fn and(&mut self, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
@@ -572,7 +695,7 @@ fn neg(&mut self, a: RValue<'gcc>) -> RValue<'gcc> {
     }
 
     fn fneg(&mut self, a: RValue<'gcc>) -> RValue<'gcc> {
-        self.cx.context.new_unary_op(None, UnaryOp::Minus, a.get_type(), a)
+        self.cx.context.new_unary_op(self.location, UnaryOp::Minus, a.get_type(), a)
     }
 
     fn not(&mut self, a: RValue<'gcc>) -> RValue<'gcc> {
@@ -580,23 +703,42 @@ fn not(&mut self, a: RValue<'gcc>) -> RValue<'gcc> {
     }
 
     fn unchecked_sadd(&mut self, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
+        if self.is_native_int_type(a.get_type()) {
+            return self.unchecked_signed_binop("__builtin_add_overflow", a, b);
+        }
         a + b
     }
 
     fn unchecked_uadd(&mut self, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
-        self.gcc_add(a, b)
+        let result = self.gcc_add(a, b);
+        if self.is_native_int_type(a.get_type()) {
+            // NOTE: unsigned overflow is defined in C, so tell GCC that it does not happen.
+            let no_overflow = self.context.new_comparison(self.location, ComparisonOp::GreaterThanEquals, result, a);
+            self.assume_condition(no_overflow);
+        }
+        result
     }
 
     fn unchecked_ssub(&mut self, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
+        if self.is_native_int_type(a.get_type()) {
+            return self.unchecked_signed_binop("__builtin_sub_overflow", a, b);
+        }
         a - b
     }
 
     fn unchecked_usub(&mut self, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
-        // TODO(antoyo): should generate poison value?
+        if self.is_native_int_type(a.get_type()) {
+            // NOTE: unsigned overflow is defined in C, so tell GCC that it does not happen.
+            let no_overflow = self.context.new_comparison(self.location, ComparisonOp::GreaterThanEquals, a, b);
+            self.assume_condition(no_overflow);
+        }
         self.gcc_sub(a, b)
     }
 
     fn unchecked_smul(&mut self, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
+        if self.is_native_int_type(a.get_type()) {
+            return self.unchecked_signed_binop("__builtin_mul_overflow", a, b);
+        }
         a * b
     }
 
@@ -604,24 +746,28 @@ fn unchecked_umul(&mut self, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
         a * b
     }
 
-    fn fadd_fast(&mut self, _lhs: RValue<'gcc>, _rhs: RValue<'gcc>) -> RValue<'gcc> {
-        unimplemented!();
+    // NOTE: libgccjit has no way to relax the floating-point semantics of a single operation and
+    // enabling -ffast-math would affect the whole crate, so the fast variants are emitted as the
+    // regular operations. This is always a valid lowering since the fast variants only allow
+    // more transformations.
+    fn fadd_fast(&mut self, lhs: RValue<'gcc>, rhs: RValue<'gcc>) -> RValue<'gcc> {
+        lhs + rhs
     }
 
-    fn fsub_fast(&mut self, _lhs: RValue<'gcc>, _rhs: RValue<'gcc>) -> RValue<'gcc> {
-        unimplemented!();
+    fn fsub_fast(&mut self, lhs: RValue<'gcc>, rhs: RValue<'gcc>) -> RValue<'gcc> {
+        lhs - rhs
     }
 
-    fn fmul_fast(&mut self, _lhs: RValue<'gcc>, _rhs: RValue<'gcc>) -> RValue<'gcc> {
-        unimplemented!();
+    fn fmul_fast(&mut self, lhs: RValue<'gcc>, rhs: RValue<'gcc>) -> RValue<'gcc> {
+        lhs * rhs
     }
 
-    fn fdiv_fast(&mut self, _lhs: RValue<'gcc>, _rhs: RValue<'gcc>) -> RValue<'gcc> {
-        unimplemented!();
+    fn fdiv_fast(&mut self, lhs: RValue<'gcc>, rhs: RValue<'gcc>) -> RValue<'gcc> {
+        lhs / rhs
     }
 
-    fn frem_fast(&mut self, _lhs: RValue<'gcc>, _rhs: RValue<'gcc>) -> RValue<'gcc> {
-        unimplemented!();
+    fn frem_fast(&mut self, lhs: RValue<'gcc>, rhs: RValue<'gcc>) -> RValue<'gcc> {
+        self.frem(lhs, rhs)
     }
 
     fn checked_binop(&mut self, oop: OverflowOp, typ: Ty<'_>, lhs: Self::Value, rhs: Self::Value) -> (Self::Value, Self::Value) {
@@ -640,36 +786,57 @@ fn alloca(&mut self, ty: Type<'gcc>, align: Align) -> RValue<'gcc> {
             };
         // TODO(antoyo): It might be better to return a LValue, but fixing the rustc API is non-trivial.
         self.stack_var_count.set(self.stack_var_count.get() + 1);
-        self.current_func().new_local(None, aligned_type, &format!("stack_var_{}", self.stack_var_count.get())).get_address(None)
+        self.current_func().new_local(self.location, aligned_type, &format!("stack_var_{}", self.stack_var_count.get())).get_address(self.location)
     }
 
-    fn dynamic_alloca(&mut self, _ty: Type<'gcc>, _align: Align) -> RValue<'gcc> {
-        unimplemented!();
+    fn dynamic_alloca(&mut self, ty: Type<'gcc>, align: Align) -> RValue<'gcc> {
+        self.alloca(ty, align)
     }
 
-    fn array_alloca(&mut self, _ty: Type<'gcc>, _len: RValue<'gcc>, _align: Align) -> RValue<'gcc> {
-        unimplemented!();
+    fn array_alloca(&mut self, ty: Type<'gcc>, len: RValue<'gcc>, align: Align) -> RValue<'gcc> {
+        // NOTE: libgccjit locals have a fixed size, so a dynamic size requires the alloca builtin.
+        // This is used by rustc_codegen_ssa to copy unsized values (unsized locals and params).
+        // TODO: use sizeof when libgccjit supports it: get_size() only works on integers.
+        let element_size = self.context.new_rvalue_from_long(self.sizet_type, ty.get_size() as i64);
+        let len = self.context.new_cast(self.location, len, self.sizet_type);
+        let size = self.context.new_binary_op(self.location, BinaryOp::Mult, self.sizet_type, len, element_size);
+        let alloca = self.context.get_builtin_function("__builtin_alloca_with_align");
+        let align = self.context.new_rvalue_from_long(self.sizet_type, align.bits() as i64);
+        let ptr = self.context.new_call(self.location, alloca, &[size, align]);
+        self.stack_var_count.set(self.stack_var_count.get() + 1);
+        let result = self.current_func().new_local(self.location, ty.make_pointer(), &format!("stack_var_{}", self.stack_var_count.get()));
+        self.llbb().add_assignment(self.location, result, self.context.new_cast(self.location, ptr, ty.make_pointer()));
+        result.to_rvalue()
     }
 
-    fn load(&mut self, pointee_ty: Type<'gcc>, ptr: RValue<'gcc>, _align: Align) -> RValue<'gcc> {
+    fn load(&mut self, pointee_ty: Type<'gcc>, ptr: RValue<'gcc>, align: Align) -> RValue<'gcc> {
         let block = self.llbb();
         let function = block.get_function();
         // NOTE: instead of returning the dereference here, we have to assign it to a variable in
         // the current basic block. Otherwise, it could be used in another basic block, causing a
         // dereference after a drop, for instance.
-        // TODO(antoyo): handle align of the load instruction.
-        let ptr = self.context.new_cast(None, ptr, pointee_ty.make_pointer());
-        let deref = ptr.dereference(None).to_rvalue();
+        // NOTE: libgccjit does not support specifying the alignment on the load, so we cast to an
+        // aligned type, like in store_with_flags. This is needed for the fields of packed structs
+        // on targets that require aligned accesses.
+        let aligned_type = pointee_ty.get_aligned(align.bytes());
+        let ptr = self.context.new_cast(self.location, ptr, aligned_type.make_pointer());
+        let deref = ptr.dereference(self.location).to_rvalue();
         unsafe { RETURN_VALUE_COUNT += 1 };
-        let loaded_value = function.new_local(None, pointee_ty, &format!("loadedValue{}", unsafe { RETURN_VALUE_COUNT }));
-        block.add_assignment(None, loaded_value, deref);
+        let loaded_value = function.new_local(self.location, pointee_ty, &format!("loadedValue{}", unsafe { RETURN_VALUE_COUNT }));
+        block.add_assignment(self.location, loaded_value, deref);
         loaded_value.to_rvalue()
     }
 
-    fn volatile_load(&mut self, _ty: Type<'gcc>, ptr: RValue<'gcc>) -> RValue<'gcc> {
-        // TODO(antoyo): use ty.
-        let ptr = self.context.new_cast(None, ptr, ptr.get_type().make_volatile());
-        ptr.dereference(None).to_rvalue()
+    fn volatile_load(&mut self, ty: Type<'gcc>, ptr: RValue<'gcc>) -> RValue<'gcc> {
+        // NOTE: the pointee needs to be volatile, not the pointer.
+        let ptr = self.context.new_cast(self.location, ptr, ty.make_volatile().make_pointer());
+        let deref = ptr.dereference(self.location).to_rvalue();
+        // NOTE: assign to a variable so that the volatile access happens exactly once, in the
+        // current basic block.
+        unsafe { RETURN_VALUE_COUNT += 1 };
+        let loaded_value = self.current_func().new_local(self.location, ty, &format!("volatileLoadedValue{}", unsafe { RETURN_VALUE_COUNT }));
+        self.llbb().add_assignment(self.location, loaded_value, deref);
+        loaded_value.to_rvalue()
     }
 
     fn atomic_load(&mut self, _ty: Type<'gcc>, ptr: RValue<'gcc>, order: AtomicOrdering, size: Size) -> RValue<'gcc> {
@@ -682,8 +849,8 @@ fn atomic_load(&mut self, _ty: Type<'gcc>, ptr: RValue<'gcc>, order: AtomicOrder
             .make_const()
             .make_volatile()
             .make_pointer();
-        let ptr = self.context.new_cast(None, ptr, volatile_const_void_ptr_type);
-        self.context.new_call(None, atomic_load, &[ptr, ordering])
+        let ptr = self.context.new_cast(self.location, ptr, volatile_const_void_ptr_type);
+        self.context.new_call(self.location, atomic_load, &[ptr, ordering])
     }
 
     fn load_operand(&mut self, place: PlaceRef<'tcx, RValue<'gcc>>) -> OperandRef<'tcx, RValue<'gcc>> {
@@ -758,7 +925,7 @@ fn write_operand_repeatedly(mut self, cg_elem: OperandRef<'tcx, RValue<'gcc>>, c
         let next_bb = self.append_sibling_block("repeat_loop_next");
 
         let ptr_type = start.get_type();
-        let current = self.llbb().get_function().new_local(None, ptr_type, "loop_var");
+        let current = self.llbb().get_function().new_local(self.location, ptr_type, "loop_var");
         let current_val = current.to_rvalue();
         self.assign(current, start);
 
@@ -773,7 +940,7 @@ fn write_operand_repeatedly(mut self, cg_elem: OperandRef<'tcx, RValue<'gcc>>, c
         cg_elem.val.store(&mut self, PlaceRef::new_sized_aligned(current_val, cg_elem.layout, align));
 
         let next = self.inbounds_gep(self.backend_type(cg_elem.layout), current.to_rvalue(), &[self.const_usize(1)]);
-        self.llbb().add_assignment(None, current, next);
+        self.llbb().add_assignment(self.location, current, next);
         self.br(header_bb);
 
         self.switch_to_block(next_bb);
@@ -792,17 +959,22 @@ fn store(&mut self, val: RValue<'gcc>, ptr: RValue<'gcc>, align: Align) -> RValu
         self.store_with_flags(val, ptr, align, MemFlags::empty())
     }
 
-    fn store_with_flags(&mut self, val: RValue<'gcc>, ptr: RValue<'gcc>, align: Align, _flags: MemFlags) -> RValue<'gcc> {
+    fn store_with_flags(&mut self, val: RValue<'gcc>, ptr: RValue<'gcc>, align: Align, flags: MemFlags) -> RValue<'gcc> {
         let ptr = self.check_store(val, ptr);
-        let destination = ptr.dereference(None);
+        let destination = ptr.dereference(self.location);
         // NOTE: libgccjit does not support specifying the alignment on the assignment, so we cast
         // to type so it gets the proper alignment.
         let destination_type = destination.to_rvalue().get_type().unqualified();
-        let aligned_type = destination_type.get_aligned(align.bytes()).make_pointer();
-        let aligned_destination = self.cx.context.new_bitcast(None, ptr, aligned_type);
-        let aligned_destination = aligned_destination.dereference(None);
-        self.llbb().add_assignment(None, aligned_destination, val);
-        // TODO(antoyo): handle align and flags.
+        let align = if flags.contains(MemFlags::UNALIGNED) { 1 } else { align.bytes() };
+        let mut modified_destination_type = destination_type.get_aligned(align);
+        if flags.contains(MemFlags::VOLATILE) {
+            modified_destination_type = modified_destination_type.make_volatile();
+        }
+        let modified_ptr_type = modified_destination_type.make_pointer();
+        let modified_destination = self.cx.context.new_bitcast(self.location, ptr, modified_ptr_type);
+        let modified_destination = modified_destination.dereference(self.location);
+        self.llbb().add_assignment(self.location, modified_destination, val);
+        // TODO: handle non-temporal stores.
         // NOTE: dummy value here since it's never used. FIXME(antoyo): API should not return a value here?
         self.cx.context.new_rvalue_zero(self.type_i32())
     }
@@ -814,21 +986,21 @@ fn atomic_store(&mut self, value: RValue<'gcc>, ptr: RValue<'gcc>, order: Atomic
         let volatile_const_void_ptr_type = self.context.new_type::<()>()
             .make_volatile()
             .make_pointer();
-        let ptr = self.context.new_cast(None, ptr, volatile_const_void_ptr_type);
+        let ptr = self.context.new_cast(self.location, ptr, volatile_const_void_ptr_type);
 
         // FIXME(antoyo): fix libgccjit to allow comparing an integer type with an aligned integer type because
         // the following cast is required to avoid this error:
         // gcc_jit_context_new_call: mismatching types for argument 2 of function "__atomic_store_4": assignment to param arg1 (type: int) from loadedValue3577 (type: unsigned int  __attribute__((aligned(4))))
         let int_type = atomic_store.get_param(1).to_rvalue().get_type();
-        let value = self.context.new_cast(None, value, int_type);
+        let value = self.context.new_cast(self.location, value, int_type);
         self.llbb()
-            .add_eval(None, self.context.new_call(None, atomic_store, &[ptr, value, ordering]));
+            .add_eval(self.location, self.context.new_call(self.location, atomic_store, &[ptr, value, ordering]));
     }
 
     fn gep(&mut self, _typ: Type<'gcc>, ptr: RValue<'gcc>, indices: &[RValue<'gcc>]) -> RValue<'gcc> {
         let mut result = ptr;
         for index in indices {
-            result = self.context.new_array_access(None, result, *index).get_address(None).to_rvalue();
+            result = self.context.new_array_access(self.location, result, *index).get_address(self.location).to_rvalue();
         }
         result
     }
@@ -838,11 +1010,11 @@ fn inbounds_gep(&mut self, _typ: Type<'gcc>, ptr: RValue<'gcc>, indices: &[RValu
         // TODO(antoyo): specify inbounds somehow.
         match indices.len() {
             1 => {
-                self.context.new_array_access(None, ptr, indices[0]).get_address(None)
+                self.context.new_array_access(self.location, ptr, indices[0]).get_address(self.location)
             },
             2 => {
-                let array = ptr.dereference(None); // TODO(antoyo): assert that first index is 0?
-                self.context.new_array_access(None, array, indices[1]).get_address(None)
+                let array = ptr.dereference(self.location); // TODO(antoyo): assert that first index is 0?
+                self.context.new_array_access(self.location, array, indices[1]).get_address(self.location)
             },
             _ => unimplemented!(),
         }
@@ -851,22 +1023,22 @@ fn inbounds_gep(&mut self, _typ: Type<'gcc>, ptr: RValue<'gcc>, indices: &[RValu
     fn struct_gep(&mut self, value_type: Type<'gcc>, ptr: RValue<'gcc>, idx: u64) -> RValue<'gcc> {
         // FIXME(antoyo): it would be better if the API only called this on struct, not on arrays.
         assert_eq!(idx as usize as u64, idx);
-        let value = ptr.dereference(None).to_rvalue();
+        let value = ptr.dereference(self.location).to_rvalue();
 
         if value_type.dyncast_array().is_some() {
             let index = self.context.new_rvalue_from_long(self.u64_type, i64::try_from(idx).expect("i64::try_from"));
-            let element = self.context.new_array_access(None, value, index);
-            element.get_address(None)
+            let element = self.context.new_array_access(self.location, value, index);
+            element.get_address(self.location)
         }
         else if let Some(vector_type) = value_type.dyncast_vector() {
             let array_type = vector_type.get_element_type().make_pointer();
             let array = self.bitcast(ptr, array_type);
             let index = self.context.new_rvalue_from_long(self.u64_type, i64::try_from(idx).expect("i64::try_from"));
-            let element = self.context.new_array_access(None, array, index);
-            element.get_address(None)
+            let element = self.context.new_array_access(self.location, array, index);
+            element.get_address(self.location)
         }
         else if let Some(struct_type) = value_type.is_struct() {
-            ptr.dereference_field(None, struct_type.get_field(idx as i32)).get_address(None)
+            ptr.dereference_field(self.location, struct_type.get_field(idx as i32)).get_address(self.location)
         }
         else {
             panic!("Unexpected type {:?}", value_type);
@@ -881,11 +1053,30 @@ fn trunc(&mut self, value: RValue<'gcc>, dest_ty: Type<'gcc>) -> RValue<'gcc> {
 
     fn sext(&mut self, value: RValue<'gcc>, dest_ty: Type<'gcc>) -> RValue<'gcc> {
         // TODO(antoyo): check that it indeed sign extend the value.
-        if dest_ty.dyncast_vector().is_some() {
-            // TODO(antoyo): nothing to do as it is only for LLVM?
-            return value;
+        if let Some(dest_vector_type) = dest_ty.dyncast_vector() {
+            // NOTE: vector comparisons in GCC already produce all ones or all zeros in each element,
+            // so only the size of the elements might need to be adjusted.
+            let vector_type = value.get_type().unqualified().dyncast_vector().expect("vector type");
+            let element_type = vector_type.get_element_type();
+            let dest_element_type = dest_vector_type.get_element_type();
+            if element_type.get_size() == dest_element_type.get_size() {
+                return self.context.new_bitcast(self.location, value, dest_ty);
+            }
+
+            let num_units = vector_type.get_num_units();
+            let array_type = self.context.new_array_type(self.location, element_type, num_units as i32);
+            // TODO: switch to using new_vector_access.
+            let array = self.context.new_bitcast(self.location, value, array_type);
+            let elements: Vec<_> = (0..num_units)
+                .map(|i| {
+                    let index = self.context.new_rvalue_from_int(self.int_type, i as i32);
+                    let element = self.context.new_array_access(self.location, array, index).to_rvalue();
+                    self.context.new_cast(self.location, element, dest_element_type)
+                })
+                .collect();
+            return self.context.new_rvalue_from_vector(self.location, dest_ty, &elements);
         }
-        self.context.new_cast(None, value, dest_ty)
+        self.context.new_cast(self.location, value, dest_ty)
     }
 
     fn fptoui(&mut self, value: RValue<'gcc>, dest_ty: Type<'gcc>) -> RValue<'gcc> {
@@ -906,11 +1097,11 @@ fn sitofp(&mut self, value: RValue<'gcc>, dest_ty: Type<'gcc>) -> RValue<'gcc> {
 
     fn fptrunc(&mut self, value: RValue<'gcc>, dest_ty: Type<'gcc>) -> RValue<'gcc> {
         // TODO(antoyo): make sure it truncates.
-        self.context.new_cast(None, value, dest_ty)
+        self.context.new_cast(self.location, value, dest_ty)
     }
 
     fn fpext(&mut self, value: RValue<'gcc>, dest_ty: Type<'gcc>) -> RValue<'gcc> {
-        self.context.new_cast(None, value, dest_ty)
+        self.context.new_cast(self.location, value, dest_ty)
     }
 
     fn ptrtoint(&mut self, value: RValue<'gcc>, dest_ty: Type<'gcc>) -> RValue<'gcc> {
@@ -924,6 +1115,15 @@ fn inttoptr(&mut self, value: RValue<'gcc>, dest_ty: Type<'gcc>) -> RValue<'gcc>
     }
 
     fn bitcast(&mut self, value: RValue<'gcc>, dest_ty: Type<'gcc>) -> RValue<'gcc> {
+        let value_type = value.get_type();
+        let is_aggregate = |typ: Type<'gcc>| typ.is_struct().is_some() || typ.dyncast_array().is_some();
+        if value_type != dest_ty && (is_aggregate(value_type) || is_aggregate(dest_ty)) {
+            // NOTE: libgccjit can only bitcast scalars, so aggregates (like the padding struct a
+            // union is lowered to) are reinterpreted through memory.
+            let local = self.current_func().new_local(self.location, value_type, "transmuted");
+            self.llbb().add_assignment(self.location, local, value);
+            return self.load(dest_ty, local.get_address(self.location), Align::ONE);
+        }
         self.cx.const_bitcast(value, dest_ty)
     }
 
@@ -938,13 +1138,13 @@ fn pointercast(&mut self, value: RValue<'gcc>, dest_ty: Type<'gcc>) -> RValue<'g
             (false, true) => {
                 // NOTE: Projecting a field of a pointer type will attempt a cast from a signed char to
                 // a pointer, which is not supported by gccjit.
-                return self.cx.context.new_cast(None, self.inttoptr(value, val_type.make_pointer()), dest_ty);
+                return self.cx.context.new_cast(self.location, self.inttoptr(value, val_type.make_pointer()), dest_ty);
             },
             (false, false) => {
                 // When they are not pointers, we want a transmute (or reinterpret_cast).
                 self.bitcast(value, dest_ty)
             },
-            (true, true) => self.cx.context.new_cast(None, value, dest_ty),
+            (true, true) => self.cx.context.new_cast(self.location, value, dest_ty),
             (true, false) => unimplemented!(),
         }
     }
@@ -955,7 +1155,7 @@ fn icmp(&mut self, op: IntPredicate, lhs: RValue<'gcc>, rhs: RValue<'gcc>) -> RV
     }
 
     fn fcmp(&mut self, op: RealPredicate, lhs: RValue<'gcc>, rhs: RValue<'gcc>) -> RValue<'gcc> {
-        self.context.new_comparison(None, op.to_gcc_comparison(), lhs, rhs)
+        self.context.new_comparison(self.location, op.to_gcc_comparison(), lhs, rhs)
     }
 
     /* Miscellaneous instructions */
@@ -967,7 +1167,7 @@ fn memcpy(&mut self, dst: RValue<'gcc>, _dst_align: Align, src: RValue<'gcc>, _s
         let src = self.pointercast(src, self.type_ptr_to(self.type_void()));
         let memcpy = self.context.get_builtin_function("memcpy");
         // TODO(antoyo): handle aligns and is_volatile.
-        self.block.add_eval(None, self.context.new_call(None, memcpy, &[dst, src, size]));
+        self.block.add_eval(self.location, self.context.new_call(self.location, memcpy, &[dst, src, size]));
     }
 
     fn memmove(&mut self, dst: RValue<'gcc>, dst_align: Align, src: RValue<'gcc>, src_align: Align, size: RValue<'gcc>, flags: MemFlags) {
@@ -985,7 +1185,7 @@ fn memmove(&mut self, dst: RValue<'gcc>, dst_align: Align, src: RValue<'gcc>, sr
 
         let memmove = self.context.get_builtin_function("memmove");
         // TODO(antoyo): handle is_volatile.
-        self.block.add_eval(None, self.context.new_call(None, memmove, &[dst, src, size]));
+        self.block.add_eval(self.location, self.context.new_call(self.location, memmove, &[dst, src, size]));
     }
 
     fn memset(&mut self, ptr: RValue<'gcc>, fill_byte: RValue<'gcc>, size: RValue<'gcc>, _align: Align, flags: MemFlags) {
@@ -993,27 +1193,27 @@ fn memset(&mut self, ptr: RValue<'gcc>, fill_byte: RValue<'gcc>, size: RValue<'g
         let ptr = self.pointercast(ptr, self.type_i8p());
         let memset = self.context.get_builtin_function("memset");
         // TODO(antoyo): handle align and is_volatile.
-        let fill_byte = self.context.new_cast(None, fill_byte, self.i32_type);
+        let fill_byte = self.context.new_cast(self.location, fill_byte, self.i32_type);
         let size = self.intcast(size, self.type_size_t(), false);
-        self.block.add_eval(None, self.context.new_call(None, memset, &[ptr, fill_byte, size]));
+        self.block.add_eval(self.location, self.context.new_call(self.location, memset, &[ptr, fill_byte, size]));
     }
 
     fn select(&mut self, cond: RValue<'gcc>, then_val: RValue<'gcc>, mut else_val: RValue<'gcc>) -> RValue<'gcc> {
         let func = self.current_func();
-        let variable = func.new_local(None, then_val.get_type(), "selectVar");
+        let variable = func.new_local(self.location, then_val.get_type(), "selectVar");
         let then_block = func.new_block("then");
         let else_block = func.new_block("else");
         let after_block = func.new_block("after");
-        self.llbb().end_with_conditional(None, cond, then_block, else_block);
+        self.llbb().end_with_conditional(self.location, cond, then_block, else_block);
 
-        then_block.add_assignment(None, variable, then_val);
-        then_block.end_with_jump(None, after_block);
+        then_block.add_assignment(self.location, variable, then_val);
+        then_block.end_with_jump(self.location, after_block);
 
         if !then_val.get_type().is_compatible_with(else_val.get_type()) {
-            else_val = self.context.new_cast(None, else_val, then_val.get_type());
+            else_val = self.context.new_cast(self.location, else_val, then_val.get_type());
         }
-        else_block.add_assignment(None, variable, else_val);
-        else_block.end_with_jump(None, after_block);
+        else_block.add_assignment(self.location, variable, else_val);
+        else_block.end_with_jump(self.location, after_block);
 
         // NOTE: since jumps were added in a place rustc does not expect, the current block in the
         // state need to be updated.
@@ -1042,8 +1242,8 @@ fn extract_value(&mut self, aggregate_value: RValue<'gcc>, idx: u64) -> RValue<'
 
         if value_type.dyncast_array().is_some() {
             let index = self.context.new_rvalue_from_long(self.u64_type, i64::try_from(idx).expect("i64::try_from"));
-            let element = self.context.new_array_access(None, aggregate_value, index);
-            element.get_address(None)
+            let element = self.context.new_array_access(self.location, aggregate_value, index);
+            element.get_address(self.location)
         }
         else if value_type.dyncast_vector().is_some() {
             panic!();
@@ -1052,14 +1252,14 @@ fn extract_value(&mut self, aggregate_value: RValue<'gcc>, idx: u64) -> RValue<'
             if let Some(struct_type) = pointer_type.is_struct() {
                 // NOTE: hack to workaround a limitation of the rustc API: see comment on
                 // CodegenCx.structs_as_pointer
-                aggregate_value.dereference_field(None, struct_type.get_field(idx as i32)).to_rvalue()
+                aggregate_value.dereference_field(self.location, struct_type.get_field(idx as i32)).to_rvalue()
             }
             else {
                 panic!("Unexpected type {:?}", value_type);
             }
         }
         else if let Some(struct_type) = value_type.is_struct() {
-            aggregate_value.access_field(None, struct_type.get_field(idx as i32)).to_rvalue()
+            aggregate_value.access_field(self.location, struct_type.get_field(idx as i32)).to_rvalue()
         }
         else {
             panic!("Unexpected type {:?}", value_type);
@@ -1071,10 +1271,10 @@ fn insert_value(&mut self, aggregate_value: RValue<'gcc>, value: RValue<'gcc>, i
         assert_eq!(idx as usize as u64, idx);
         let value_type = aggregate_value.get_type();
 
-        let lvalue =
+        let (lvalue, aggregate_value) =
             if value_type.dyncast_array().is_some() {
                 let index = self.context.new_rvalue_from_long(self.u64_type, i64::try_from(idx).expect("i64::try_from"));
-                self.context.new_array_access(None, aggregate_value, index)
+                (self.context.new_array_access(self.location, aggregate_value, index), aggregate_value)
             }
             else if value_type.dyncast_vector().is_some() {
                 panic!();
@@ -1083,12 +1283,22 @@ fn insert_value(&mut self, aggregate_value: RValue<'gcc>, value: RValue<'gcc>, i
                 if let Some(struct_type) = pointer_type.is_struct() {
                     // NOTE: hack to workaround a limitation of the rustc API: see comment on
                     // CodegenCx.structs_as_pointer
-                    aggregate_value.dereference_field(None, struct_type.get_field(idx as i32))
+                    (aggregate_value.dereference_field(self.location, struct_type.get_field(idx as i32)), aggregate_value)
                 }
                 else {
                     panic!("Unexpected type {:?}", value_type);
                 }
             }
+            else if let Some(struct_type) = value_type.is_struct() {
+                // NOTE: a scalar pair coming from a call or a load is a struct rvalue: copy it to a
+                // local so that its field can be assigned and return it as a pointer, like the
+                // values created by const_undef.
+                let local = self.current_func().new_local(self.location, value_type, "scalarPair");
+                self.llbb().add_assignment(self.location, local, aggregate_value);
+                let pointer = local.get_address(self.location);
+                self.structs_as_pointer.borrow_mut().insert(pointer);
+                (pointer.dereference_field(self.location, struct_type.get_field(idx as i32)), pointer)
+            }
             else {
                 panic!("Unexpected type {:?}", value_type);
             };
@@ -1097,13 +1307,13 @@ fn insert_value(&mut self, aggregate_value: RValue<'gcc>, value: RValue<'gcc>, i
         let value =
             // NOTE: sometimes, rustc will create a value with the wrong type.
             if lvalue_type != value.get_type() {
-                self.context.new_cast(None, value, lvalue_type)
+                self.context.new_cast(self.location, value, lvalue_type)
             }
             else {
                 value
             };
 
-        self.llbb().add_assignment(None, lvalue, value);
+        self.llbb().add_assignment(self.location, lvalue, value);
 
         aggregate_value
     }
@@ -1113,10 +1323,10 @@ fn set_personality_fn(&mut self, _personality: RValue<'gcc>) {
     }
 
     fn cleanup_landing_pad(&mut self, _ty: Type<'gcc>, _pers_fn: RValue<'gcc>) -> RValue<'gcc> {
-        let field1 = self.context.new_field(None, self.u8_type.make_pointer(), "landing_pad_field_1");
-        let field2 = self.context.new_field(None, self.i32_type, "landing_pad_field_1");
-        let struct_type = self.context.new_struct_type(None, "landing_pad", &[field1, field2]);
-        self.current_func().new_local(None, struct_type.as_type(), "landing_pad")
+        let field1 = self.context.new_field(self.location, self.u8_type.make_pointer(), "landing_pad_field_1");
+        let field2 = self.context.new_field(self.location, self.i32_type, "landing_pad_field_1");
+        let struct_type = self.context.new_struct_type(self.location, "landing_pad", &[field1, field2]);
+        self.current_func().new_local(self.location, struct_type.as_type(), "landing_pad")
             .to_rvalue()
         // TODO(antoyo): Properly implement unwinding.
         // the above is just to make the compilation work as it seems
@@ -1151,20 +1361,20 @@ fn catch_switch(
 
     // Atomic Operations
     fn atomic_cmpxchg(&mut self, dst: RValue<'gcc>, cmp: RValue<'gcc>, src: RValue<'gcc>, order: AtomicOrdering, failure_order: AtomicOrdering, weak: bool) -> RValue<'gcc> {
-        let expected = self.current_func().new_local(None, cmp.get_type(), "expected");
-        self.llbb().add_assignment(None, expected, cmp);
+        let expected = self.current_func().new_local(self.location, cmp.get_type(), "expected");
+        self.llbb().add_assignment(self.location, expected, cmp);
         let success = self.compare_exchange(dst, expected, src, order, failure_order, weak);
 
         let pair_type = self.cx.type_struct(&[src.get_type(), self.bool_type], false);
-        let result = self.current_func().new_local(None, pair_type, "atomic_cmpxchg_result");
+        let result = self.current_func().new_local(self.location, pair_type, "atomic_cmpxchg_result");
         let align = Align::from_bits(64).expect("align"); // TODO(antoyo): use good align.
 
         let value_type = result.to_rvalue().get_type();
         if let Some(struct_type) = value_type.is_struct() {
-            self.store(success, result.access_field(None, struct_type.get_field(1)).get_address(None), align);
+            self.store(success, result.access_field(self.location, struct_type.get_field(1)).get_address(self.location), align);
             // NOTE: since success contains the call to the intrinsic, it must be stored before
             // expected so that we store expected after the call.
-            self.store(expected.to_rvalue(), result.access_field(None, struct_type.get_field(0)).get_address(None), align);
+            self.store(expected.to_rvalue(), result.access_field(self.location, struct_type.get_field(0)).get_address(self.location), align);
         }
         // TODO(antoyo): handle when value is not a struct.
 
@@ -1182,10 +1392,10 @@ fn atomic_rmw(&mut self, op: AtomicRmwBinOp, dst: RValue<'gcc>, src: RValue<'gcc
                 AtomicRmwBinOp::AtomicNand => format!("__atomic_fetch_nand_{}", size),
                 AtomicRmwBinOp::AtomicOr => format!("__atomic_fetch_or_{}", size),
                 AtomicRmwBinOp::AtomicXor => format!("__atomic_fetch_xor_{}", size),
-                AtomicRmwBinOp::AtomicMax => return self.atomic_extremum(ExtremumOperation::Max, dst, src, order),
-                AtomicRmwBinOp::AtomicMin => return self.atomic_extremum(ExtremumOperation::Min, dst, src, order),
-                AtomicRmwBinOp::AtomicUMax => return self.atomic_extremum(ExtremumOperation::Max, dst, src, order),
-                AtomicRmwBinOp::AtomicUMin => return self.atomic_extremum(ExtremumOperation::Min, dst, src, order),
+                AtomicRmwBinOp::AtomicMax => return self.atomic_extremum(ExtremumOperation::Max, dst, src, order, true),
+                AtomicRmwBinOp::AtomicMin => return self.atomic_extremum(ExtremumOperation::Min, dst, src, order, true),
+                AtomicRmwBinOp::AtomicUMax => return self.atomic_extremum(ExtremumOperation::Max, dst, src, order, false),
+                AtomicRmwBinOp::AtomicUMin => return self.atomic_extremum(ExtremumOperation::Min, dst, src, order, false),
             };
 
 
@@ -1194,12 +1404,12 @@ fn atomic_rmw(&mut self, op: AtomicRmwBinOp, dst: RValue<'gcc>, src: RValue<'gcc
 
         let void_ptr_type = self.context.new_type::<*mut ()>();
         let volatile_void_ptr_type = void_ptr_type.make_volatile();
-        let dst = self.context.new_cast(None, dst, volatile_void_ptr_type);
+        let dst = self.context.new_cast(self.location, dst, volatile_void_ptr_type);
         // FIXME(antoyo): not sure why, but we have the wrong type here.
         let new_src_type = atomic_function.get_param(1).to_rvalue().get_type();
-        let src = self.context.new_cast(None, src, new_src_type);
-        let res = self.context.new_call(None, atomic_function, &[dst, src, order]);
-        self.context.new_cast(None, res, src.get_type())
+        let src = self.context.new_cast(self.location, src, new_src_type);
+        let res = self.context.new_call(self.location, atomic_function, &[dst, src, order]);
+        self.context.new_cast(self.location, res, src.get_type())
     }
 
     fn atomic_fence(&mut self, order: AtomicOrdering, scope: SynchronizationScope) {
@@ -1210,7 +1420,7 @@ fn atomic_fence(&mut self, order: AtomicOrdering, scope: SynchronizationScope) {
             };
         let thread_fence = self.context.get_builtin_function(name);
         let order = self.context.new_rvalue_from_int(self.i32_type, order.to_gcc());
-        self.llbb().add_eval(None, self.context.new_call(None, thread_fence, &[order]));
+        self.llbb().add_eval(self.location, self.context.new_call(self.location, thread_fence, &[order]));
     }
 
     fn set_invariant_load(&mut self, load: RValue<'gcc>) {
@@ -1414,7 +1624,10 @@ fn fptoint_sat(&mut self, signed: bool, val: RValue<'gcc>, dest_ty: Type<'gcc>)
 
         // Step 1 ...
         let fptosui_result = if signed { self.fptosi(val, dest_ty) } else { self.fptoui(val, dest_ty) };
-        let less_or_nan = self.fcmp(RealPredicate::RealULT, val, f_min);
+        // NOTE: GCC has no unordered comparisons, so `val < f_min || val is NaN` is computed as
+        // `!(val >= f_min)`.
+        let greater_or_equal = self.fcmp(RealPredicate::RealOGE, val, f_min);
+        let less_or_nan = self.not(greater_or_equal);
         let greater = self.fcmp(RealPredicate::RealOGT, val, f_max);
 
         // Step 2: We use two comparisons and two selects, with %s1 being the
@@ -1423,17 +1636,11 @@ fn fptoint_sat(&mut self, signed: bool, val: RValue<'gcc>, dest_ty: Type<'gcc>)
         //     %greater = fcmp olt %val, %f_max
         //     %s0 = select %less_or_nan, int_ty::MIN, %fptosi_result
         //     %s1 = select %greater, int_ty::MAX, %s0
-        // Note that %less_or_nan uses an *unordered* comparison. This
-        // comparison is true if the operands are not comparable (i.e., if val is
-        // NaN). The unordered comparison ensures that s1 becomes int_ty::MIN if
-        // val is NaN.
+        // Note that %less_or_nan is true if the operands are not comparable (i.e., if val is
+        // NaN). This ensures that s1 becomes int_ty::MIN if val is NaN.
         //
-        // Performance note: Unordered comparison can be lowered to a "flipped"
-        // comparison and a negation, and the negation can be merged into the
-        // select. Therefore, it not necessarily any more expensive than an
-        // ordered ("normal") comparison. Whether these optimizations will be
-        // performed is ultimately up to the backend, but at least x86 does
-        // perform them.
+        // Performance note: GCC turns these selects into conditional moves, so no branch remains
+        // in the optimized code.
         let s0 = self.select(less_or_nan, int_min, fptosui_result);
         let s1 = self.select(greater, int_max, s0);
 
@@ -1474,7 +1681,7 @@ pub fn shuffle_vector(&mut self, v1: RValue<'gcc>, v2: RValue<'gcc>, mask: RValu
             };
         for i in 0..mask_num_units {
             let field = struct_type.get_field(i as i32);
-            vector_elements.push(self.context.new_cast(None, mask.access_field(None, field).to_rvalue(), mask_element_type));
+            vector_elements.push(self.context.new_cast(self.location, mask.access_field(self.location, field).to_rvalue(), mask_element_type));
         }
 
         // NOTE: the mask needs to be the same length as the input vectors, so add the missing
@@ -1483,26 +1690,26 @@ pub fn shuffle_vector(&mut self, v1: RValue<'gcc>, v2: RValue<'gcc>, mask: RValu
             vector_elements.push(self.context.new_rvalue_zero(mask_element_type));
         }
 
-        let array_type = self.context.new_array_type(None, element_type, vec_num_units as i32);
+        let array_type = self.context.new_array_type(self.location, element_type, vec_num_units as i32);
         let result_type = self.context.new_vector_type(element_type, mask_num_units as u64);
         let (v1, v2) =
             if vec_num_units < mask_num_units {
                 // NOTE: the mask needs to be the same length as the input vectors, so join the 2
                 // vectors and create a dummy second vector.
                 // TODO(antoyo): switch to using new_vector_access.
-                let array = self.context.new_bitcast(None, v1, array_type);
+                let array = self.context.new_bitcast(self.location, v1, array_type);
                 let mut elements = vec![];
                 for i in 0..vec_num_units {
-                    elements.push(self.context.new_array_access(None, array, self.context.new_rvalue_from_int(self.int_type, i as i32)).to_rvalue());
+                    elements.push(self.context.new_array_access(self.location, array, self.context.new_rvalue_from_int(self.int_type, i as i32)).to_rvalue());
                 }
                 // TODO(antoyo): switch to using new_vector_access.
-                let array = self.context.new_bitcast(None, v2, array_type);
+                let array = self.context.new_bitcast(self.location, v2, array_type);
                 for i in 0..(mask_num_units - vec_num_units) {
-                    elements.push(self.context.new_array_access(None, array, self.context.new_rvalue_from_int(self.int_type, i as i32)).to_rvalue());
+                    elements.push(self.context.new_array_access(self.location, array, self.context.new_rvalue_from_int(self.int_type, i as i32)).to_rvalue());
                 }
-                let v1 = self.context.new_rvalue_from_vector(None, result_type, &elements);
+                let v1 = self.context.new_rvalue_from_vector(self.location, result_type, &elements);
                 let zero = self.context.new_rvalue_zero(element_type);
-                let v2 = self.context.new_rvalue_from_vector(None, result_type, &vec![zero; mask_num_units]);
+                let v2 = self.context.new_rvalue_from_vector(self.location, result_type, &vec![zero; mask_num_units]);
                 (v1, v2)
             }
             else {
@@ -1511,19 +1718,19 @@ pub fn shuffle_vector(&mut self, v1: RValue<'gcc>, v2: RValue<'gcc>, mask: RValu
 
         let new_mask_num_units = std::cmp::max(mask_num_units, vec_num_units);
         let mask_type = self.context.new_vector_type(mask_element_type, new_mask_num_units as u64);
-        let mask = self.context.new_rvalue_from_vector(None, mask_type, &vector_elements);
-        let result = self.context.new_rvalue_vector_perm(None, v1, v2, mask);
+        let mask = self.context.new_rvalue_from_vector(self.location, mask_type, &vector_elements);
+        let result = self.context.new_rvalue_vector_perm(self.location, v1, v2, mask);
 
         if vec_num_units != mask_num_units {
             // NOTE: if padding was added, only select the number of elements of the masks to
             // remove that padding in the result.
             let mut elements = vec![];
             // TODO(antoyo): switch to using new_vector_access.
-            let array = self.context.new_bitcast(None, result, array_type);
+            let array = self.context.new_bitcast(self.location, result, array_type);
             for i in 0..mask_num_units {
-                elements.push(self.context.new_array_access(None, array, self.context.new_rvalue_from_int(self.int_type, i as i32)).to_rvalue());
+                elements.push(self.context.new_array_access(self.location, array, self.context.new_rvalue_from_int(self.int_type, i as i32)).to_rvalue());
             }
-            self.context.new_rvalue_from_vector(None, result_type, &elements)
+            self.context.new_rvalue_from_vector(self.location, result_type, &elements)
         }
         else {
             result
@@ -1531,8 +1738,37 @@ pub fn shuffle_vector(&mut self, v1: RValue<'gcc>, v2: RValue<'gcc>, mask: RValu
     }
 
     #[cfg(not(feature="master"))]
-    pub fn shuffle_vector(&mut self, _v1: RValue<'gcc>, _v2: RValue<'gcc>, _mask: RValue<'gcc>) -> RValue<'gcc> {
-        unimplemented!();
+    pub fn shuffle_vector(&mut self, v1: RValue<'gcc>, v2: RValue<'gcc>, mask: RValue<'gcc>) -> RValue<'gcc> {
+        // NOTE: vector permutation is not available, so copy both vectors in an array and pick the
+        // elements one by one.
+        let struct_type = mask.get_type().is_struct().expect("mask of struct type");
+
+        // TODO: use a recursive unqualified() here.
+        let vector_type = v1.get_type().unqualified().dyncast_vector().expect("vector type");
+        let element_type = vector_type.get_element_type();
+        let vec_num_units = vector_type.get_num_units();
+        let mask_num_units = struct_type.get_field_count();
+
+        let array_type = self.context.new_array_type(self.location, element_type, vec_num_units as i32);
+        let operands_type = self.context.new_array_type(self.location, element_type, (vec_num_units * 2) as i32);
+        let operands = self.current_func().new_local(self.location, operands_type, "shuffle_operands");
+        for (offset, vector) in [(0, v1), (vec_num_units, v2)] {
+            let array = self.context.new_bitcast(self.location, vector, array_type);
+            for i in 0..vec_num_units {
+                let element = self.context.new_array_access(self.location, array, self.context.new_rvalue_from_int(self.int_type, i as i32)).to_rvalue();
+                let index = self.context.new_rvalue_from_int(self.int_type, (offset + i) as i32);
+                self.llbb().add_assignment(self.location, self.context.new_array_access(self.location, operands, index), element);
+            }
+        }
+
+        let mut elements = vec![];
+        for i in 0..mask_num_units {
+            let field = struct_type.get_field(i as i32);
+            let index = self.context.new_cast(self.location, mask.access_field(self.location, field).to_rvalue(), self.int_type);
+            elements.push(self.context.new_array_access(self.location, operands, index).to_rvalue());
+        }
+        let result_type = self.context.new_vector_type(element_type, mask_num_units as u64);
+        self.context.new_rvalue_from_vector(self.location, result_type, &elements)
     }
 
     #[cfg(feature="master")]
@@ -1553,12 +1789,12 @@ pub fn vector_reduce<F>(&mut self, src: RValue<'gcc>, op: F) -> RValue<'gcc>
                 vector_elements.iter()
                     .map(|i| self.context.new_rvalue_from_int(self.int_type, ((i + shift) % element_count) as i32))
                     .collect();
-            let mask = self.context.new_rvalue_from_vector(None, mask_type, &vector_elements);
-            let shifted = self.context.new_rvalue_vector_perm(None, res, res, mask);
+            let mask = self.context.new_rvalue_from_vector(self.location, mask_type, &vector_elements);
+            let shifted = self.context.new_rvalue_vector_perm(self.location, res, res, mask);
             shift *= 2;
             res = op(res, shifted, &self.context);
         }
-        self.context.new_vector_access(None, res, self.context.new_rvalue_zero(self.int_type))
+        self.context.new_vector_access(self.location, res, self.context.new_rvalue_zero(self.int_type))
             .to_rvalue()
     }
 
@@ -1573,12 +1809,16 @@ pub fn vector_reduce_op(&mut self, src: RValue<'gcc>, op: BinaryOp) -> RValue<'g
         self.vector_reduce(src, |a, b, context| context.new_binary_op(None, op, a.get_type(), a, b))
     }
 
-    pub fn vector_reduce_fadd_fast(&mut self, _acc: RValue<'gcc>, _src: RValue<'gcc>) -> RValue<'gcc> {
-        unimplemented!();
+    // NOTE: the fast reductions are allowed to reassociate, so the elements are combined pairwise,
+    // which can be done with vector operations.
+    pub fn vector_reduce_fadd_fast(&mut self, acc: RValue<'gcc>, src: RValue<'gcc>) -> RValue<'gcc> {
+        let sum = self.vector_reduce_op(src, BinaryOp::Plus);
+        acc + sum
     }
 
-    pub fn vector_reduce_fmul_fast(&mut self, _acc: RValue<'gcc>, _src: RValue<'gcc>) -> RValue<'gcc> {
-        unimplemented!();
+    pub fn vector_reduce_fmul_fast(&mut self, acc: RValue<'gcc>, src: RValue<'gcc>) -> RValue<'gcc> {
+        let product = self.vector_reduce_op(src, BinaryOp::Mult);
+        acc * product
     }
 
     // Inspired by Hacker's Delight min implementation.
@@ -1600,25 +1840,28 @@ pub fn vector_reduce_max(&mut self, src: RValue<'gcc>) -> RValue<'gcc> {
     pub fn vector_select(&mut self, cond: RValue<'gcc>, then_val: RValue<'gcc>, else_val: RValue<'gcc>) -> RValue<'gcc> {
         // cond is a vector of integers, not of bools.
         let cond_type = cond.get_type();
-        let vector_type = cond_type.unqualified().dyncast_vector().expect("vector type");
-        let num_units = vector_type.get_num_units();
-        let element_type = vector_type.get_element_type();
-        let zeros = vec![self.context.new_rvalue_zero(element_type); num_units];
-        let zeros = self.context.new_rvalue_from_vector(None, cond_type, &zeros);
-
-        let masks = self.context.new_comparison(None, ComparisonOp::NotEquals, cond, zeros);
-        let then_vals = masks & then_val;
-
-        let ones = vec![self.context.new_rvalue_one(element_type); num_units];
-        let ones = self.context.new_rvalue_from_vector(None, cond_type, &ones);
-        let inverted_masks = masks + ones;
-        // NOTE: sometimes, the type of else_val can be different than the type of then_val in
-        // libgccjit (vector of int vs vector of int32_t), but they should be the same for the AND
-        // operation to work.
-        let else_val = self.context.new_bitcast(None, else_val, then_val.get_type());
-        let else_vals = inverted_masks & else_val;
-
-        then_vals | else_vals
+        let cond_vector_type = cond_type.unqualified().dyncast_vector().expect("vector type");
+        let num_units = cond_vector_type.get_num_units();
+        let cond_element_type = cond_vector_type.get_element_type();
+        let zeros = vec![self.context.new_rvalue_zero(cond_element_type); num_units];
+        let zeros = self.context.new_rvalue_from_vector(self.location, cond_type, &zeros);
+
+        // NOTE: the comparison produces integers of the size of the condition elements, so resize
+        // them to the size of the selected elements.
+        let element_type = then_val.get_type().unqualified().dyncast_vector().expect("vector type").get_element_type();
+        let mask_element_type = self.cx.type_ix(element_type.get_size() as u64 * 8);
+        let mask_type = self.context.new_vector_type(mask_element_type, num_units as u64);
+        let masks = self.context.new_comparison(self.location, ComparisonOp::NotEquals, cond, zeros);
+        let masks = self.sext(masks, mask_type);
+        let inverted_masks = self.context.new_unary_op(self.location, UnaryOp::BitwiseNegate, mask_type, masks);
+
+        // NOTE: select the bits as integers since floating-point vectors do not support the bitwise
+        // operations. This also handles the case where the type of else_val is different than the
+        // type of then_val in libgccjit (vector of int vs vector of int32_t).
+        let then_vals = masks & self.context.new_bitcast(self.location, then_val, mask_type);
+        let else_vals = inverted_masks & self.context.new_bitcast(self.location, else_val, mask_type);
+
+        self.context.new_bitcast(self.location, then_vals | else_vals, then_val.get_type())
     }
 }
 
@@ -1713,8 +1956,9 @@ fn to_gcc(self) -> i32 {
 
         let ordering =
             match self {
+                // NOTE: GCC has no ordering weaker than relaxed, which is the same as LLVM's monotonic.
                 AtomicOrdering::Unordered => __ATOMIC_RELAXED,
-                AtomicOrdering::Relaxed => __ATOMIC_RELAXED, // TODO(antoyo): check if that's the same.
+                AtomicOrdering::Relaxed => __ATOMIC_RELAXED,
                 AtomicOrdering::Acquire => __ATOMIC_ACQUIRE,
                 AtomicOrdering::Release => __ATOMIC_RELEASE,
                 AtomicOrdering::AcquireRelease => __ATOMIC_ACQ_REL,
@@ -207,10 +207,21 @@ fn scalar_to_backend(&self, cv: Scalar, layout: abi::Scalar, ty: Type<'gcc>) ->
                             self.get_static(def_id).get_address(None)
                         },
                     };
-                let ptr_type = base_addr.get_type();
-                let base_addr = self.const_bitcast(base_addr, self.usize_type);
-                let offset = self.context.new_rvalue_from_long(self.usize_type, offset.bytes() as i64);
-                let ptr = self.const_bitcast(base_addr + offset, ptr_type);
+                // NOTE: keep the address as is when there's no offset, so that the entries of
+                // vtables are plain function addresses: this allows GCC to fold a load from a
+                // read-only vtable into a direct call (devirtualization).
+                // TODO: emit the type information used by the IPA devirtualization of GCC when
+                // libgccjit supports it.
+                let ptr =
+                    if offset.bytes() == 0 {
+                        base_addr
+                    }
+                    else {
+                        let ptr_type = base_addr.get_type();
+                        let base_addr = self.const_bitcast(base_addr, self.usize_type);
+                        let offset = self.context.new_rvalue_from_long(self.usize_type, offset.bytes() as i64);
+                        self.const_bitcast(base_addr + offset, ptr_type)
+                    };
                 if layout.primitive() != Pointer {
                     self.const_bitcast(ptr.dereference(None).to_rvalue(), ty)
                 }
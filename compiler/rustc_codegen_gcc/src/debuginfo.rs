@@ -1,9 +1,11 @@
-use gccjit::RValue;
-use rustc_codegen_ssa::mir::debuginfo::{FunctionDebugContext, VariableKind};
+use gccjit::{Location, RValue};
+use rustc_codegen_ssa::mir::debuginfo::{DebugScope, FunctionDebugContext, VariableKind};
 use rustc_codegen_ssa::traits::{DebugInfoBuilderMethods, DebugInfoMethods};
+use rustc_index::vec::IndexVec;
 use rustc_middle::mir;
 use rustc_middle::ty::{Instance, PolyExistentialTraitRef, Ty};
-use rustc_span::{SourceFile, Span, Symbol};
+use rustc_session::config::DebugInfo;
+use rustc_span::{BytePos, SourceFile, Span, Symbol};
 use rustc_target::abi::Size;
 use rustc_target::abi::call::FnAbi;
 
@@ -13,8 +15,8 @@
 impl<'a, 'gcc, 'tcx> DebugInfoBuilderMethods for Builder<'a, 'gcc, 'tcx> {
     // FIXME(eddyb) find a common convention for all of the debuginfo-related
     // names (choose between `dbg`, `debug`, `debuginfo`, `debug_info` etc.).
-    fn dbg_var_addr(&mut self, _dbg_var: Self::DIVariable, _scope_metadata: Self::DIScope, _variable_alloca: Self::Value, _direct_offset: Size, _indirect_offsets: &[Size]) {
-        unimplemented!();
+    fn dbg_var_addr(&mut self, _dbg_var: Self::DIVariable, _dbg_loc: Self::DILocation, _variable_alloca: Self::Value, _direct_offset: Size, _indirect_offsets: &[Size]) {
+        // TODO(antoyo): implement variable debuginfo.
     }
 
     fn insert_reference_to_gdb_debug_scripts_section_global(&mut self) {
@@ -22,11 +24,11 @@ fn insert_reference_to_gdb_debug_scripts_section_global(&mut self) {
     }
 
     fn set_var_name(&mut self, _value: RValue<'gcc>, _name: &str) {
-        unimplemented!();
+        // TODO: implement when libgccjit supports naming rvalues.
     }
 
-    fn set_dbg_loc(&mut self, _dbg_loc: Self::DILocation) {
-        unimplemented!();
+    fn set_dbg_loc(&mut self, dbg_loc: Self::DILocation) {
+        self.location = Some(dbg_loc);
     }
 }
 
@@ -35,13 +37,27 @@ fn create_vtable_debuginfo(&self, _ty: Ty<'tcx>, _trait_ref: Option<PolyExistent
         // TODO(antoyo)
     }
 
-    fn create_function_debug_context(&self, _instance: Instance<'tcx>, _fn_abi: &FnAbi<'tcx, Ty<'tcx>>, _llfn: RValue<'gcc>, _mir: &mir::Body<'tcx>) -> Option<FunctionDebugContext<Self::DIScope, Self::DILocation>> {
-        // TODO(antoyo)
-        None
+    fn create_function_debug_context(&self, _instance: Instance<'tcx>, _fn_abi: &FnAbi<'tcx, Ty<'tcx>>, _llfn: RValue<'gcc>, mir: &mir::Body<'tcx>) -> Option<FunctionDebugContext<Self::DIScope, Self::DILocation>> {
+        if self.sess().opts.debuginfo == DebugInfo::None {
+            return None;
+        }
+
+        // NOTE: libgccjit has no notion of scope: the locations carry the file name, so every
+        // scope covers all the files and never needs to be extended.
+        // TODO: handle the inlined scopes.
+        let scope = DebugScope {
+            dbg_scope: (),
+            inlined_at: None,
+            file_start_pos: BytePos(0),
+            file_end_pos: BytePos(u32::MAX),
+        };
+        Some(FunctionDebugContext {
+            scopes: IndexVec::from_elem(scope, &mir.source_scopes),
+        })
     }
 
-    fn extend_scope_to_file(&self, _scope_metadata: Self::DIScope, _file: &SourceFile) -> Self::DIScope {
-        unimplemented!();
+    fn extend_scope_to_file(&self, scope_metadata: Self::DIScope, _file: &SourceFile) -> Self::DIScope {
+        scope_metadata
     }
 
     fn debuginfo_finalize(&self) {
@@ -49,14 +65,26 @@ fn debuginfo_finalize(&self) {
     }
 
     fn create_dbg_var(&self, _variable_name: Symbol, _variable_type: Ty<'tcx>, _scope_metadata: Self::DIScope, _variable_kind: VariableKind, _span: Span) -> Self::DIVariable {
-        unimplemented!();
+        // TODO(antoyo): implement variable debuginfo.
     }
 
     fn dbg_scope_fn(&self, _instance: Instance<'tcx>, _fn_abi: &FnAbi<'tcx, Ty<'tcx>>, _maybe_definition_llfn: Option<RValue<'gcc>>) -> Self::DIScope {
         unimplemented!();
     }
 
-    fn dbg_loc(&self, _scope: Self::DIScope, _inlined_at: Option<Self::DILocation>, _span: Span) -> Self::DILocation {
-        unimplemented!();
+    fn dbg_loc(&self, _scope: Self::DIScope, _inlined_at: Option<Self::DILocation>, span: Span) -> Self::DILocation {
+        self.span_to_location(span)
+    }
+}
+
+impl<'gcc, 'tcx> CodegenCx<'gcc, 'tcx> {
+    /// Returns the location of `span`. It is used for the debuginfo as well as to make the
+    /// diagnostics of GCC about asm point to the Rust source.
+    pub fn span_to_location(&self, span: Span) -> Location<'gcc> {
+        let source_map = self.sess().source_map();
+        let loc = source_map.lookup_char_pos(span.lo());
+        let filename = loc.file.name.prefer_remapped().to_string();
+        // NOTE: columns are 1-based in DWARF.
+        self.context.new_location(filename, loc.line as i32, loc.col.0 as i32 + 1)
     }
 }
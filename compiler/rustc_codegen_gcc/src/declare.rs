@@ -2,10 +2,11 @@
 use rustc_codegen_ssa::traits::BaseTypeMethods;
 use rustc_middle::ty::Ty;
 use rustc_span::Symbol;
-use rustc_target::abi::call::FnAbi;
+use rustc_target::abi::call::{Conv, FnAbi};
 
 use crate::abi::FnAbiGccExt;
 use crate::context::CodegenCx;
+use crate::errors::CallingConventionNotSupported;
 use crate::intrinsic::llvm;
 
 impl<'gcc, 'tcx> CodegenCx<'gcc, 'tcx> {
@@ -83,10 +84,23 @@ pub fn declare_fn(&self, name: &str, fn_abi: &FnAbi<'tcx, Ty<'tcx>>) -> RValue<'
         let (return_type, params, variadic, on_stack_param_indices) = fn_abi.gcc_type(self);
         let func = declare_raw_fn(self, name, () /*fn_abi.llvm_cconv()*/, return_type, &params, variadic);
         self.on_stack_function_params.borrow_mut().insert(func, on_stack_param_indices);
+        // NOTE: only record the convention here since declaring a function that is never defined
+        // nor called is harmless: see check_calling_convention.
+        if let Some(conv) = unsupported_calling_convention(self, fn_abi) {
+            self.unsupported_calling_conventions.borrow_mut().insert(func, conv);
+        }
         // FIXME(antoyo): this is a wrong cast. That requires changing the compiler API.
         unsafe { std::mem::transmute(func) }
     }
 
+    /// Reports an error when defining or calling a function whose calling convention cannot be
+    /// set with libgccjit, since it would otherwise silently use the C one.
+    pub fn check_calling_convention(&self, func: Function<'gcc>) {
+        if let Some(&conv) = self.unsupported_calling_conventions.borrow().get(&func) {
+            self.sess().emit_fatal(CallingConventionNotSupported { conv });
+        }
+    }
+
     pub fn define_global(&self, name: &str, ty: Type<'gcc>, is_tls: bool, link_section: Option<Symbol>) -> LValue<'gcc> {
         self.get_or_insert_global(name, ty, is_tls, link_section)
     }
@@ -97,6 +111,29 @@ pub fn get_declared_value(&self, name: &str) -> Option<RValue<'gcc>> {
     }
 }
 
+/// Returns the name of the calling convention of `fn_abi` if it differs from the C one and libgccjit
+/// cannot set it.
+fn unsupported_calling_convention<'gcc, 'tcx>(cx: &CodegenCx<'gcc, 'tcx>, fn_abi: &FnAbi<'tcx, Ty<'tcx>>) -> Option<&'static str> {
+    // NOTE: the conventions that differ from the C one are only kept by rustc on x86 targets.
+    let is_like_windows = cx.sess().target.is_like_windows;
+    match fn_abi.conv {
+        Conv::X86Stdcall => Some("stdcall"),
+        Conv::X86Fastcall => Some("fastcall"),
+        Conv::X86ThisCall => Some("thiscall"),
+        Conv::X86VectorCall => Some("vectorcall"),
+        // NOTE: the 64-bit ABIs are fine when they are the default one of the target.
+        Conv::X86_64Win64 if !is_like_windows => Some("win64"),
+        Conv::X86_64SysV if is_like_windows => Some("sysv64"),
+        // NOTE: interrupt handlers need the special prologue and epilogue emitted by the
+        // interrupt attribute of GCC.
+        Conv::X86Intr => Some("x86-interrupt"),
+        Conv::AvrInterrupt => Some("avr-interrupt"),
+        Conv::AvrNonBlockingInterrupt => Some("avr-non-blocking-interrupt"),
+        Conv::Msp430Intr => Some("msp430-interrupt"),
+        _ => None,
+    }
+}
+
 /// Declare a function.
 ///
 /// If there’s a value with the same name already declared, the function will
@@ -3,6 +3,8 @@
 use rustc_middle::ty::Ty;
 use rustc_span::{Span, Symbol};
 use std::borrow::Cow;
+use std::io;
+use std::path::Path;
 
 struct ExitCode(Option<i32>);
 
@@ -223,6 +225,17 @@ pub(crate) struct InvalidMonomorphizationUnsupportedOperation<'a> {
     pub in_elem: Ty<'a>,
 }
 
+#[derive(Diagnostic)]
+#[diag(codegen_gcc::invalid_monomorphization_expected_pointer_vector, code = "E0511")]
+pub(crate) struct InvalidMonomorphizationExpectedPointerVector<'a> {
+    #[primary_span]
+    pub span: Span,
+    pub name: Symbol,
+    pub vec_ty: Ty<'a>,
+    pub in_elem: Ty<'a>,
+    pub mutability: &'static str,
+}
+
 #[derive(Diagnostic)]
 #[diag(codegen_gcc::linkage_const_or_mut_type)]
 pub(crate) struct LinkageConstOrMutType {
@@ -230,13 +243,80 @@ pub(crate) struct LinkageConstOrMutType {
     pub span: Span
 }
 
+#[derive(Diagnostic)]
+#[diag(codegen_gcc::calling_convention_not_supported)]
+#[note]
+pub(crate) struct CallingConventionNotSupported {
+    pub conv: &'static str,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_gcc::invalid_minimum_alignment)]
+pub(crate) struct InvalidMinimumAlignment {
+    pub err: String,
+}
+
 #[derive(Diagnostic)]
 #[diag(codegen_gcc::lto_not_supported)]
 pub(crate) struct LTONotSupported;
 
+#[derive(Diagnostic)]
+#[diag(codegen_gcc::split_debuginfo_not_supported)]
+pub(crate) struct SplitDebuginfoNotSupported;
+
+#[derive(Diagnostic)]
+#[diag(codegen_gcc::c_variadic_not_supported)]
+#[note]
+pub(crate) struct CVariadicNotSupported;
+
+#[derive(Diagnostic)]
+#[diag(codegen_gcc::c_variadic_intrinsic_not_supported)]
+#[note]
+pub(crate) struct CVariadicIntrinsicNotSupported {
+    #[primary_span]
+    pub span: Span,
+    pub name: Symbol,
+}
+
 #[derive(Diagnostic)]
 #[diag(codegen_gcc::unwinding_inline_asm)]
 pub(crate) struct UnwindingInlineAsm {
     #[primary_span]
     pub span: Span
 }
+
+#[derive(Diagnostic)]
+#[diag(codegen_gcc::wasm_inline_asm)]
+pub(crate) struct WasmInlineAsm {
+    #[primary_span]
+    pub span: Span
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_gcc::unsupported_asm_register_class)]
+pub(crate) struct UnsupportedAsmRegisterClass {
+    #[primary_span]
+    pub span: Span,
+    pub reg_class: Symbol,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_gcc::unsupported_asm_clobber)]
+pub(crate) struct UnsupportedAsmClobber {
+    #[primary_span]
+    pub span: Span,
+    pub reg: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_gcc::dump_reproducer_failed)]
+pub(crate) struct DumpReproducerFailed<'a> {
+    pub path: &'a Path,
+    pub error: io::Error,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_gcc::non_utf8_path)]
+pub(crate) struct NonUtf8Path<'a> {
+    pub path: &'a Path,
+}
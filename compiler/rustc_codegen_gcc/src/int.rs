@@ -25,7 +25,7 @@ pub fn gcc_srem(&self, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
 
     pub fn gcc_not(&self, a: RValue<'gcc>) -> RValue<'gcc> {
         let typ = a.get_type();
-        if self.is_native_int_type_or_bool(typ) {
+        if self.is_native_int_type_or_bool(typ) || typ.is_vector() {
             let operation =
                 if typ.is_bool() {
                     UnaryOp::LogicalNegate
@@ -33,28 +33,28 @@ pub fn gcc_not(&self, a: RValue<'gcc>) -> RValue<'gcc> {
                 else {
                     UnaryOp::BitwiseNegate
                 };
-            self.cx.context.new_unary_op(None, operation, typ, a)
+            self.cx.context.new_unary_op(self.location, operation, typ, a)
         }
         else {
             // TODO(antoyo): use __negdi2 and __negti2 instead?
             let element_type = typ.dyncast_array().expect("element type");
             let values = [
-                self.cx.context.new_unary_op(None, UnaryOp::BitwiseNegate, element_type, self.low(a)),
-                self.cx.context.new_unary_op(None, UnaryOp::BitwiseNegate, element_type, self.high(a)),
+                self.cx.context.new_unary_op(self.location, UnaryOp::BitwiseNegate, element_type, self.low(a)),
+                self.cx.context.new_unary_op(self.location, UnaryOp::BitwiseNegate, element_type, self.high(a)),
             ];
-            self.cx.context.new_array_constructor(None, typ, &values)
+            self.cx.context.new_array_constructor(self.location, typ, &values)
         }
     }
 
     pub fn gcc_neg(&self, a: RValue<'gcc>) -> RValue<'gcc> {
         let a_type = a.get_type();
-        if self.is_native_int_type(a_type) {
-            self.cx.context.new_unary_op(None, UnaryOp::Minus, a.get_type(), a)
+        if self.is_native_int_type(a_type) || a_type.is_vector() {
+            self.cx.context.new_unary_op(self.location, UnaryOp::Minus, a.get_type(), a)
         }
         else {
-            let param_a = self.context.new_parameter(None, a_type, "a");
-            let func = self.context.new_function(None, FunctionType::Extern, a_type, &[param_a], "__negti2", false);
-            self.context.new_call(None, func, &[a])
+            let param_a = self.context.new_parameter(self.location, a_type, "a");
+            let func = self.context.new_function(self.location, FunctionType::Extern, a_type, &[param_a], "__negti2", false);
+            self.context.new_call(self.location, func, &[a])
         }
     }
 
@@ -67,11 +67,16 @@ pub fn gcc_lshr(&mut self, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
         let b_type = b.get_type();
         let a_native = self.is_native_int_type(a_type);
         let b_native = self.is_native_int_type(b_type);
-        if a_native && b_native {
+        if a_type.is_vector() && b_type.is_vector() {
+            // NOTE: whether the shift is logical or arithmetic depends on the signedness of the
+            // element type.
+            a >> b
+        }
+        else if a_native && b_native {
             // FIXME(antoyo): remove the casts when libgccjit can shift an unsigned number by a signed number.
             // TODO(antoyo): cast to unsigned to do a logical shift if that does not work.
             if a_type.is_signed(self) != b_type.is_signed(self) {
-                let b = self.context.new_cast(None, b, a_type);
+                let b = self.context.new_cast(self.location, b, a_type);
                 a >> b
             }
             else {
@@ -94,14 +99,14 @@ pub fn gcc_lshr(&mut self, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
             let b0_block = func.new_block("b0");
             let actual_else_block = func.new_block("actual_else");
 
-            let result = func.new_local(None, a_type, "shiftResult");
+            let result = func.new_local(self.location, a_type, "shiftResult");
 
             let sixty_four = self.gcc_int(native_int_type, 64);
             let sixty_three = self.gcc_int(native_int_type, 63);
             let zero = self.gcc_zero(native_int_type);
             let b = self.gcc_int_cast(b, native_int_type);
             let condition = self.gcc_icmp(IntPredicate::IntNE, self.gcc_and(b, sixty_four), zero);
-            self.llbb().end_with_conditional(None, condition, then_block, else_block);
+            self.llbb().end_with_conditional(self.location, condition, then_block, else_block);
 
             // TODO(antoyo): take endianness into account.
             let shift_value = self.gcc_sub(b, sixty_four);
@@ -117,29 +122,29 @@ pub fn gcc_lshr(&mut self, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
                 high >> shift_value,
                 sign,
             ];
-            let array_value = self.context.new_array_constructor(None, a_type, &values);
-            then_block.add_assignment(None, result, array_value);
-            then_block.end_with_jump(None, after_block);
+            let array_value = self.context.new_array_constructor(self.location, a_type, &values);
+            then_block.add_assignment(self.location, result, array_value);
+            then_block.end_with_jump(self.location, after_block);
 
             let condition = self.gcc_icmp(IntPredicate::IntEQ, b, zero);
-            else_block.end_with_conditional(None, condition, b0_block, actual_else_block);
+            else_block.end_with_conditional(self.location, condition, b0_block, actual_else_block);
 
-            b0_block.add_assignment(None, result, a);
-            b0_block.end_with_jump(None, after_block);
+            b0_block.add_assignment(self.location, result, a);
+            b0_block.end_with_jump(self.location, after_block);
 
             let shift_value = self.gcc_sub(sixty_four, b);
             // NOTE: cast low to its unsigned type in order to perform a logical right shift.
             let unsigned_type = native_int_type.to_unsigned(&self.cx);
-            let casted_low = self.context.new_cast(None, self.low(a), unsigned_type);
-            let shifted_low = casted_low >> self.context.new_cast(None, b, unsigned_type);
-            let shifted_low = self.context.new_cast(None, shifted_low, native_int_type);
+            let casted_low = self.context.new_cast(self.location, self.low(a), unsigned_type);
+            let shifted_low = casted_low >> self.context.new_cast(self.location, b, unsigned_type);
+            let shifted_low = self.context.new_cast(self.location, shifted_low, native_int_type);
             let values = [
                 (high << shift_value) | shifted_low,
                 high >> b,
             ];
-            let array_value = self.context.new_array_constructor(None, a_type, &values);
-            actual_else_block.add_assignment(None, result, array_value);
-            actual_else_block.end_with_jump(None, after_block);
+            let array_value = self.context.new_array_constructor(self.location, a_type, &values);
+            actual_else_block.add_assignment(self.location, result, array_value);
+            actual_else_block.end_with_jump(self.location, after_block);
 
             // NOTE: since jumps were added in a place rustc does not expect, the current block in the
             // state need to be updated.
@@ -152,18 +157,18 @@ pub fn gcc_lshr(&mut self, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
     fn additive_operation(&self, operation: BinaryOp, a: RValue<'gcc>, mut b: RValue<'gcc>) -> RValue<'gcc> {
         let a_type = a.get_type();
         let b_type = b.get_type();
-        if self.is_native_int_type_or_bool(a_type) && self.is_native_int_type_or_bool(b_type) {
+        if (self.is_native_int_type_or_bool(a_type) && self.is_native_int_type_or_bool(b_type)) || (a_type.is_vector() && b_type.is_vector()) {
             if a_type != b_type {
                 if a_type.is_vector() {
                     // Vector types need to be bitcast.
                     // TODO(antoyo): perhaps use __builtin_convertvector for vector casting.
-                    b = self.context.new_bitcast(None, b, a.get_type());
+                    b = self.context.new_bitcast(self.location, b, a.get_type());
                 }
                 else {
-                    b = self.context.new_cast(None, b, a.get_type());
+                    b = self.context.new_cast(self.location, b, a.get_type());
                 }
             }
-            self.context.new_binary_op(None, operation, a_type, a, b)
+            self.context.new_binary_op(self.location, operation, a_type, a, b)
         }
         else {
             let signed = a_type.is_compatible_with(self.i128_type);
@@ -175,10 +180,10 @@ fn additive_operation(&self, operation: BinaryOp, a: RValue<'gcc>, mut b: RValue
                     (BinaryOp::Minus, false) => "__rust_u128_sub",
                     _ => unreachable!("unexpected additive operation {:?}", operation),
                 };
-            let param_a = self.context.new_parameter(None, a_type, "a");
-            let param_b = self.context.new_parameter(None, b_type, "b");
-            let func = self.context.new_function(None, FunctionType::Extern, a_type, &[param_a, param_b], func_name, false);
-            self.context.new_call(None, func, &[a, b])
+            let param_a = self.context.new_parameter(self.location, a_type, "a");
+            let param_b = self.context.new_parameter(self.location, b_type, "b");
+            let func = self.context.new_function(self.location, FunctionType::Extern, a_type, &[param_a, param_b], func_name, false);
+            self.context.new_call(self.location, func, &[a, b])
         }
     }
 
@@ -197,8 +202,20 @@ pub fn gcc_sub(&self, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
     fn multiplicative_operation(&self, operation: BinaryOp, operation_name: &str, signed: bool, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
         let a_type = a.get_type();
         let b_type = b.get_type();
-        if self.is_native_int_type_or_bool(a_type) && self.is_native_int_type_or_bool(b_type) {
-            self.context.new_binary_op(None, operation, a_type, a, b)
+        if a_type.is_vector() && b_type.is_vector() {
+            // NOTE: the signedness of the element type is already the one of the operation.
+            self.context.new_binary_op(self.location, operation, a_type, a, b)
+        }
+        else if self.is_native_int_type_or_bool(a_type) && self.is_native_int_type_or_bool(b_type) {
+            if matches!(operation, BinaryOp::Mult) {
+                return self.context.new_binary_op(self.location, operation, a_type, a, b);
+            }
+
+            // NOTE: the result of a division or a remainder depends on the signedness of the
+            // operands in GCC, so make sure they match the requested operation.
+            let (a, b) = self.convert_signedness(signed, a, b);
+            let result = self.context.new_binary_op(self.location, operation, a.get_type(), a, b);
+            self.context.new_cast(self.location, result, a_type)
         }
         else {
             let sign =
@@ -208,18 +225,38 @@ fn multiplicative_operation(&self, operation: BinaryOp, operation_name: &str, si
                 else {
                     "u"
                 };
+            let (a, b) =
+                if matches!(operation, BinaryOp::Mult) {
+                    (a, b)
+                }
+                else {
+                    self.convert_signedness(signed, a, b)
+                };
+            // TODO: call __udivmodti4 or __divmodti4 once when both the quotient and the remainder of the
+            // same operands are needed: rustc requests them as separate operations.
             let func_name = format!("__{}{}ti3", sign, operation_name);
-            let param_a = self.context.new_parameter(None, a_type, "a");
-            let param_b = self.context.new_parameter(None, b_type, "b");
-            let func = self.context.new_function(None, FunctionType::Extern, a_type, &[param_a, param_b], func_name, false);
-            self.context.new_call(None, func, &[a, b])
+            let param_a = self.context.new_parameter(self.location, a.get_type(), "a");
+            let param_b = self.context.new_parameter(self.location, b.get_type(), "b");
+            let func = self.context.new_function(self.location, FunctionType::Extern, a.get_type(), &[param_a, param_b], func_name, false);
+            let result = self.context.new_call(self.location, func, &[a, b]);
+            self.gcc_int_cast(result, a_type)
         }
     }
 
+    /// Cast both operands to the signed or unsigned type of the same width.
+    fn convert_signedness(&self, signed: bool, a: RValue<'gcc>, b: RValue<'gcc>) -> (RValue<'gcc>, RValue<'gcc>) {
+        let typ =
+            if signed {
+                a.get_type().to_signed(self)
+            }
+            else {
+                a.get_type().to_unsigned(self)
+            };
+        (self.gcc_int_cast(a, typ), self.gcc_int_cast(b, typ))
+    }
+
     pub fn gcc_sdiv(&self, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
-        // TODO(antoyo): check if the types are signed?
         // 128-bit, signed: __divti3
-        // TODO(antoyo): convert the arguments to signed?
         self.multiplicative_operation(BinaryOp::Divide, "div", true, a, b)
     }
 
@@ -319,15 +356,15 @@ pub fn gcc_checked_binop(&self, oop: OverflowOp, typ: Ty<'_>, lhs: <Self as Back
                             };
                         let a_type = lhs.get_type();
                         let b_type = rhs.get_type();
-                        let param_a = self.context.new_parameter(None, a_type, "a");
-                        let param_b = self.context.new_parameter(None, b_type, "b");
-                        let result_field = self.context.new_field(None, a_type, "result");
-                        let overflow_field = self.context.new_field(None, self.bool_type, "overflow");
-                        let return_type = self.context.new_struct_type(None, "result_overflow", &[result_field, overflow_field]);
-                        let func = self.context.new_function(None, FunctionType::Extern, return_type.as_type(), &[param_a, param_b], func_name, false);
-                        let result = self.context.new_call(None, func, &[lhs, rhs]);
-                        let overflow = result.access_field(None, overflow_field);
-                        let int_result = result.access_field(None, result_field);
+                        let param_a = self.context.new_parameter(self.location, a_type, "a");
+                        let param_b = self.context.new_parameter(self.location, b_type, "b");
+                        let result_field = self.context.new_field(self.location, a_type, "result");
+                        let overflow_field = self.context.new_field(self.location, self.bool_type, "overflow");
+                        let return_type = self.context.new_struct_type(self.location, "result_overflow", &[result_field, overflow_field]);
+                        let func = self.context.new_function(self.location, FunctionType::Extern, return_type.as_type(), &[param_a, param_b], func_name, false);
+                        let result = self.context.new_call(self.location, func, &[lhs, rhs]);
+                        let overflow = result.access_field(self.location, overflow_field);
+                        let int_result = result.access_field(self.location, result_field);
                         return (int_result, overflow);
                     },
                     _ => {
@@ -347,17 +384,30 @@ pub fn gcc_checked_binop(&self, oop: OverflowOp, typ: Ty<'_>, lhs: <Self as Back
         let intrinsic = self.context.get_builtin_function(&name);
         let res = self.current_func()
             // TODO(antoyo): is it correct to use rhs type instead of the parameter typ?
-            .new_local(None, rhs.get_type(), "binopResult")
-            .get_address(None);
+            .new_local(self.location, rhs.get_type(), "binopResult")
+            .get_address(self.location);
         let overflow = self.overflow_call(intrinsic, &[lhs, rhs, res], None);
-        (res.dereference(None).to_rvalue(), overflow)
+        (res.dereference(self.location).to_rvalue(), overflow)
     }
 
     pub fn gcc_icmp(&self, op: IntPredicate, mut lhs: RValue<'gcc>, mut rhs: RValue<'gcc>) -> RValue<'gcc> {
         let a_type = lhs.get_type();
         let b_type = rhs.get_type();
         if self.is_non_native_int_type(a_type) || self.is_non_native_int_type(b_type) {
-            let signed = a_type.is_compatible_with(self.i128_type);
+            // NOTE: the comparison function takes two operands of the same type, so convert the
+            // native one if needed.
+            if self.is_native_int_type_or_bool(a_type) {
+                lhs = self.gcc_int_cast(lhs, b_type);
+            }
+            else if self.is_native_int_type_or_bool(b_type) {
+                rhs = self.gcc_int_cast(rhs, a_type);
+            }
+            let a_type = lhs.get_type();
+            let b_type = rhs.get_type();
+
+            // NOTE: the signedness of the comparison comes from the predicate, not from the type
+            // of the operands.
+            let signed = matches!(op, IntPredicate::IntSGT | IntPredicate::IntSGE | IntPredicate::IntSLT | IntPredicate::IntSLE);
             let sign =
                 if signed {
                     ""
@@ -366,17 +416,17 @@ pub fn gcc_icmp(&self, op: IntPredicate, mut lhs: RValue<'gcc>, mut rhs: RValue<
                     "u"
                 };
             let func_name = format!("__{}cmpti2", sign);
-            let param_a = self.context.new_parameter(None, a_type, "a");
-            let param_b = self.context.new_parameter(None, b_type, "b");
-            let func = self.context.new_function(None, FunctionType::Extern, self.int_type, &[param_a, param_b], func_name, false);
-            let cmp = self.context.new_call(None, func, &[lhs, rhs]);
+            let param_a = self.context.new_parameter(self.location, a_type, "a");
+            let param_b = self.context.new_parameter(self.location, b_type, "b");
+            let func = self.context.new_function(self.location, FunctionType::Extern, self.int_type, &[param_a, param_b], func_name, false);
+            let cmp = self.context.new_call(self.location, func, &[lhs, rhs]);
             let (op, limit) =
                 match op {
                     IntPredicate::IntEQ => {
-                        return self.context.new_comparison(None, ComparisonOp::Equals, cmp, self.context.new_rvalue_one(self.int_type));
+                        return self.context.new_comparison(self.location, ComparisonOp::Equals, cmp, self.context.new_rvalue_one(self.int_type));
                     },
                     IntPredicate::IntNE => {
-                        return self.context.new_comparison(None, ComparisonOp::NotEquals, cmp, self.context.new_rvalue_one(self.int_type));
+                        return self.context.new_comparison(self.location, ComparisonOp::NotEquals, cmp, self.context.new_rvalue_one(self.int_type));
                     },
                     IntPredicate::IntUGT => (ComparisonOp::Equals, 2),
                     IntPredicate::IntUGE => (ComparisonOp::GreaterThanEquals, 1),
@@ -387,7 +437,7 @@ pub fn gcc_icmp(&self, op: IntPredicate, mut lhs: RValue<'gcc>, mut rhs: RValue<
                     IntPredicate::IntSLT => (ComparisonOp::Equals, 0),
                     IntPredicate::IntSLE => (ComparisonOp::LessThanEquals, 1),
                 };
-            self.context.new_comparison(None, op, cmp, self.context.new_rvalue_from_int(self.int_type, limit))
+            self.context.new_comparison(self.location, op, cmp, self.context.new_rvalue_from_int(self.int_type, limit))
         }
         else {
             let left_type = lhs.get_type();
@@ -395,22 +445,22 @@ pub fn gcc_icmp(&self, op: IntPredicate, mut lhs: RValue<'gcc>, mut rhs: RValue<
             if left_type != right_type {
                 // NOTE: because libgccjit cannot compare function pointers.
                 if left_type.dyncast_function_ptr_type().is_some() && right_type.dyncast_function_ptr_type().is_some() {
-                    lhs = self.context.new_cast(None, lhs, self.usize_type.make_pointer());
-                    rhs = self.context.new_cast(None, rhs, self.usize_type.make_pointer());
+                    lhs = self.context.new_cast(self.location, lhs, self.usize_type.make_pointer());
+                    rhs = self.context.new_cast(self.location, rhs, self.usize_type.make_pointer());
                 }
                 // NOTE: hack because we try to cast a vector type to the same vector type.
                 else if format!("{:?}", left_type) != format!("{:?}", right_type) {
-                    rhs = self.context.new_cast(None, rhs, left_type);
+                    rhs = self.context.new_cast(self.location, rhs, left_type);
                 }
             }
-            self.context.new_comparison(None, op.to_gcc_comparison(), lhs, rhs)
+            self.context.new_comparison(self.location, op.to_gcc_comparison(), lhs, rhs)
         }
     }
 
     pub fn gcc_xor(&self, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
         let a_type = a.get_type();
         let b_type = b.get_type();
-        if self.is_native_int_type_or_bool(a_type) && self.is_native_int_type_or_bool(b_type) {
+        if (self.is_native_int_type_or_bool(a_type) && self.is_native_int_type_or_bool(b_type)) || (a_type.is_vector() && b_type.is_vector()) {
             a ^ b
         }
         else {
@@ -418,7 +468,7 @@ pub fn gcc_xor(&self, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
                 self.low(a) ^ self.low(b),
                 self.high(a) ^ self.high(b),
             ];
-            self.context.new_array_constructor(None, a_type, &values)
+            self.context.new_array_constructor(self.location, a_type, &values)
         }
     }
 
@@ -427,15 +477,18 @@ pub fn gcc_shl(&mut self, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
         let b_type = b.get_type();
         let a_native = self.is_native_int_type(a_type);
         let b_native = self.is_native_int_type(b_type);
-        if a_native && b_native {
+        if a_type.is_vector() && b_type.is_vector() {
+            a << b
+        }
+        else if a_native && b_native {
             // FIXME(antoyo): remove the casts when libgccjit can shift an unsigned number by an unsigned number.
             if a_type.is_unsigned(self) && b_type.is_signed(self) {
-                let a = self.context.new_cast(None, a, b_type);
+                let a = self.context.new_cast(self.location, a, b_type);
                 let result = a << b;
-                self.context.new_cast(None, result, a_type)
+                self.context.new_cast(self.location, result, a_type)
             }
             else if a_type.is_signed(self) && b_type.is_unsigned(self) {
-                let b = self.context.new_cast(None, b, a_type);
+                let b = self.context.new_cast(self.location, b, a_type);
                 a << b
             }
             else {
@@ -456,42 +509,42 @@ pub fn gcc_shl(&mut self, a: RValue<'gcc>, b: RValue<'gcc>) -> RValue<'gcc> {
             let b0_block = func.new_block("b0");
             let actual_else_block = func.new_block("actual_else");
 
-            let result = func.new_local(None, a_type, "shiftResult");
+            let result = func.new_local(self.location, a_type, "shiftResult");
 
             let b = self.gcc_int_cast(b, native_int_type);
             let sixty_four = self.gcc_int(native_int_type, 64);
             let zero = self.gcc_zero(native_int_type);
             let condition = self.gcc_icmp(IntPredicate::IntNE, self.gcc_and(b, sixty_four), zero);
-            self.llbb().end_with_conditional(None, condition, then_block, else_block);
+            self.llbb().end_with_conditional(self.location, condition, then_block, else_block);
 
             // TODO(antoyo): take endianness into account.
             let values = [
                 zero,
                 self.low(a) << (b - sixty_four),
             ];
-            let array_value = self.context.new_array_constructor(None, a_type, &values);
-            then_block.add_assignment(None, result, array_value);
-            then_block.end_with_jump(None, after_block);
+            let array_value = self.context.new_array_constructor(self.location, a_type, &values);
+            then_block.add_assignment(self.location, result, array_value);
+            then_block.end_with_jump(self.location, after_block);
 
             let condition = self.gcc_icmp(IntPredicate::IntEQ, b, zero);
-            else_block.end_with_conditional(None, condition, b0_block, actual_else_block);
+            else_block.end_with_conditional(self.location, condition, b0_block, actual_else_block);
 
-            b0_block.add_assignment(None, result, a);
-            b0_block.end_with_jump(None, after_block);
+            b0_block.add_assignment(self.location, result, a);
+            b0_block.end_with_jump(self.location, after_block);
 
             // NOTE: cast low to its unsigned type in order to perform a logical right shift.
             let unsigned_type = native_int_type.to_unsigned(&self.cx);
-            let casted_low = self.context.new_cast(None, self.low(a), unsigned_type);
-            let shift_value = self.context.new_cast(None, sixty_four - b, unsigned_type);
-            let high_low = self.context.new_cast(None, casted_low >> shift_value, native_int_type);
+            let casted_low = self.context.new_cast(self.location, self.low(a), unsigned_type);
+            let shift_value = self.context.new_cast(self.location, sixty_four - b, unsigned_type);
+            let high_low = self.context.new_cast(self.location, casted_low >> shift_value, native_int_type);
             let values = [
                 self.low(a) << b,
                 (self.high(a) << b) | high_low,
             ];
 
-            let array_value = self.context.new_array_constructor(None, a_type, &values);
-            actual_else_block.add_assignment(None, result, array_value);
-            actual_else_block.end_with_jump(None, after_block);
+            let array_value = self.context.new_array_constructor(self.location, a_type, &values);
+            actual_else_block.add_assignment(self.location, result, array_value);
+            actual_else_block.end_with_jump(self.location, after_block);
 
             // NOTE: since jumps were added in a place rustc does not expect, the current block in the
             // state need to be updated.
@@ -505,16 +558,16 @@ pub fn gcc_bswap(&mut self, mut arg: RValue<'gcc>, width: u64) -> RValue<'gcc> {
         let arg_type = arg.get_type();
         if !self.is_native_int_type(arg_type) {
             let native_int_type = arg_type.dyncast_array().expect("get element type");
-            let lsb = self.context.new_array_access(None, arg, self.context.new_rvalue_from_int(self.int_type, 0)).to_rvalue();
+            let lsb = self.context.new_array_access(self.location, arg, self.context.new_rvalue_from_int(self.int_type, 0)).to_rvalue();
             let swapped_lsb = self.gcc_bswap(lsb, width / 2);
-            let swapped_lsb = self.context.new_cast(None, swapped_lsb, native_int_type);
-            let msb = self.context.new_array_access(None, arg, self.context.new_rvalue_from_int(self.int_type, 1)).to_rvalue();
+            let swapped_lsb = self.context.new_cast(self.location, swapped_lsb, native_int_type);
+            let msb = self.context.new_array_access(self.location, arg, self.context.new_rvalue_from_int(self.int_type, 1)).to_rvalue();
             let swapped_msb = self.gcc_bswap(msb, width / 2);
-            let swapped_msb = self.context.new_cast(None, swapped_msb, native_int_type);
+            let swapped_msb = self.context.new_cast(self.location, swapped_msb, native_int_type);
 
             // NOTE: we also need to swap the two elements here, in addition to swapping inside
             // the elements themselves like done above.
-            return self.context.new_array_constructor(None, arg_type, &[swapped_msb, swapped_lsb]);
+            return self.context.new_array_constructor(self.location, arg_type, &[swapped_msb, swapped_lsb]);
         }
 
         // TODO(antoyo): check if it's faster to use string literals and a
@@ -526,7 +579,7 @@ pub fn gcc_bswap(&mut self, mut arg: RValue<'gcc>, width: u64) -> RValue<'gcc> {
         if param_type != arg_type {
             arg = self.bitcast(arg, param_type);
         }
-        self.cx.context.new_call(None, bswap, &[arg])
+        self.cx.context.new_call(self.location, bswap, &[arg])
     }
 }
 
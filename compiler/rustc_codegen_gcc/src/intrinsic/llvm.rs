@@ -1,8 +1,9 @@
 use std::borrow::Cow;
 
-use gccjit::{Function, FunctionPtrType, RValue, ToRValue};
+use gccjit::{Function, FunctionPtrType, FunctionType, RValue, ToRValue};
 
 use crate::{context::CodegenCx, builder::Builder};
+use crate::declare::mangle_name;
 
 pub fn adjust_intrinsic_arguments<'a, 'b, 'gcc, 'tcx>(builder: &Builder<'a, 'gcc, 'tcx>, gcc_func: FunctionPtrType<'gcc>, mut args: Cow<'b, [RValue<'gcc>]>, func_name: &str) -> Cow<'b, [RValue<'gcc>]> {
     // Some LLVM intrinsics do not map 1-to-1 to GCC intrinsics, so we add the missing
@@ -131,6 +132,39 @@ pub fn adjust_intrinsic_arguments<'a, 'b, 'gcc, 'tcx>(builder: &Builder<'a, 'gcc
                         new_args.push(last_arg);
                         args = new_args.into();
                     },
+                    "__builtin_ia32_permvarsi512_mask" | "__builtin_ia32_permvardi512_mask"
+                        | "__builtin_ia32_permvarsf512_mask" | "__builtin_ia32_permvardf512_mask"
+                        | "__builtin_ia32_permvardi256_mask" | "__builtin_ia32_permvardf256_mask" => {
+                        // NOTE: with a mask of all ones, the source operand is never selected.
+                        let mut new_args = args.to_vec();
+                        let arg3_type = gcc_func.get_param_type(2);
+                        let undefined = builder.current_func().new_local(None, arg3_type, "undefined_for_intrinsic").to_rvalue();
+                        new_args.push(undefined);
+                        let arg4_type = gcc_func.get_param_type(3);
+                        new_args.push(builder.context.new_rvalue_from_int(arg4_type, -1));
+                        args = new_args.into();
+                    },
+                    "__builtin_ia32_vpermi2vard512_mask" | "__builtin_ia32_vpermi2varq512_mask"
+                        | "__builtin_ia32_vpermi2varps512_mask" | "__builtin_ia32_vpermi2varpd512_mask" => {
+                        let mut new_args = args.to_vec();
+                        let arg4_type = gcc_func.get_param_type(3);
+                        new_args.push(builder.context.new_rvalue_from_int(arg4_type, -1));
+                        args = new_args.into();
+                    },
+                    "__builtin_ia32_addcarryx_u32" | "__builtin_ia32_addcarryx_u64"
+                        | "__builtin_ia32_sbb_u32" | "__builtin_ia32_sbb_u64"
+                        | "__builtin_ia32_rdrand16_step" | "__builtin_ia32_rdrand32_step"
+                        | "__builtin_ia32_rdrand64_step" | "__builtin_ia32_rdseed_hi_step"
+                        | "__builtin_ia32_rdseed_si_step" | "__builtin_ia32_rdseed_di_step" => {
+                        // NOTE: the LLVM intrinsics return a second value while the GCC builtins
+                        // write it through the last parameter: see adjust_intrinsic_return_value.
+                        let mut new_args = args.to_vec();
+                        let last_param_type = gcc_func.get_param_type(gcc_func.get_param_count() - 1);
+                        let result_type = last_param_type.get_pointee().expect("pointer type");
+                        let result = builder.current_func().new_local(None, result_type, "intrinsicResult");
+                        new_args.push(result.get_address(None));
+                        args = new_args.into();
+                    },
                     _ => (),
         }
     }
@@ -138,6 +172,43 @@ pub fn adjust_intrinsic_arguments<'a, 'b, 'gcc, 'tcx>(builder: &Builder<'a, 'gcc
     args
 }
 
+/// Build the value returned by an LLVM intrinsic when the GCC builtin returns a part of it through
+/// the pointer added by adjust_intrinsic_arguments.
+pub fn adjust_intrinsic_return_value<'a, 'gcc, 'tcx>(builder: &Builder<'a, 'gcc, 'tcx>, return_value: RValue<'gcc>, func_name: &str, args: &[RValue<'gcc>]) -> RValue<'gcc> {
+    let value_through_pointer_is_first =
+        match func_name {
+            // The LLVM intrinsics return (carry, result).
+            "__builtin_ia32_addcarryx_u32" | "__builtin_ia32_addcarryx_u64"
+                | "__builtin_ia32_sbb_u32" | "__builtin_ia32_sbb_u64" => false,
+            // The LLVM intrinsics return (random value, success).
+            "__builtin_ia32_rdrand16_step" | "__builtin_ia32_rdrand32_step"
+                | "__builtin_ia32_rdrand64_step" | "__builtin_ia32_rdseed_hi_step"
+                | "__builtin_ia32_rdseed_si_step" | "__builtin_ia32_rdseed_di_step" => true,
+            _ => return return_value,
+        };
+
+    // NOTE: the call must be evaluated before reading the value it wrote through the pointer.
+    let func = builder.current_func();
+    let call_result = func.new_local(None, return_value.get_type(), "intrinsicCallResult");
+    builder.block.add_assignment(None, call_result, return_value);
+    let value_through_pointer = args.last().expect("last arg").dereference(None).to_rvalue();
+
+    let (first_value, second_value) =
+        if value_through_pointer_is_first {
+            (value_through_pointer, builder.context.new_cast(None, call_result.to_rvalue(), builder.u32_type))
+        }
+        else {
+            (builder.context.new_cast(None, call_result.to_rvalue(), builder.u8_type), value_through_pointer)
+        };
+    let field1 = builder.context.new_field(None, first_value.get_type(), "first");
+    let field2 = builder.context.new_field(None, second_value.get_type(), "second");
+    let struct_type = builder.context.new_struct_type(None, format!("{}Result", func_name), &[field1, field2]);
+    let result = func.new_local(None, struct_type.as_type(), "intrinsicReturnValue");
+    builder.block.add_assignment(None, result.access_field(None, field1), first_value);
+    builder.block.add_assignment(None, result.access_field(None, field2), second_value);
+    result.to_rvalue()
+}
+
 pub fn ignore_arg_cast(func_name: &str, index: usize, args_len: usize) -> bool {
     // NOTE: these intrinsics have missing parameters before the last one, so ignore the
     // last argument type check.
@@ -168,8 +239,62 @@ pub fn ignore_arg_cast(func_name: &str, index: usize, args_len: usize) -> bool {
     false
 }
 
+/// Hint intrinsics, like the ones used by `core::hint::spin_loop`, are lowered to inline assembly
+/// since GCC does not provide a builtin for all of them (e.g. `isb` on AArch64).
+fn hint_intrinsic<'gcc, 'tcx>(name: &str, cx: &CodegenCx<'gcc, 'tcx>) -> Option<Function<'gcc>> {
+    // NOTE: the instruction emitted for `llvm.arm.hint` is selected by its argument.
+    let instructions: &[&str] =
+        match name {
+            "llvm.x86.sse2.pause" => &["pause"],
+            // NOTE: `sy` is the only barrier option supported by `isb`, so the argument is ignored.
+            "llvm.aarch64.isb" | "llvm.arm.isb" => &["isb"],
+            "llvm.arm.hint" => &["nop", "yield", "wfe", "wfi", "sev", "sevl"],
+            _ => return None,
+        };
+
+    if let Some(func) = cx.functions.borrow().get(name).cloned() {
+        return Some(func);
+    }
+
+    let params =
+        if name == "llvm.x86.sse2.pause" {
+            vec![]
+        }
+        else {
+            vec![cx.context.new_parameter(None, cx.i32_type, "hint")]
+        };
+    let void_type = cx.context.new_type::<()>();
+    let func = cx.context.new_function(None, FunctionType::AlwaysInline, void_type, &params, mangle_name(name), false);
+    let block = func.new_block("start");
+    if let [instruction] = instructions {
+        block.add_extended_asm(None, instruction).set_volatile_flag(true);
+        block.end_with_void_return(None);
+    }
+    else {
+        let after_block = func.new_block("after");
+        let cases: Vec<_> = instructions.iter().enumerate()
+            .map(|(index, instruction)| {
+                let case_block = func.new_block(*instruction);
+                case_block.add_extended_asm(None, instruction).set_volatile_flag(true);
+                case_block.end_with_jump(None, after_block);
+                let value = cx.context.new_rvalue_from_int(cx.i32_type, index as i32);
+                cx.context.new_case(value, value, case_block)
+            })
+            .collect();
+        block.end_with_switch(None, func.get_param(0).to_rvalue(), after_block, &cases);
+        after_block.end_with_void_return(None);
+    }
+
+    cx.functions.borrow_mut().insert(name.to_string(), func);
+    Some(func)
+}
+
 #[cfg(not(feature="master"))]
 pub fn intrinsic<'gcc, 'tcx>(name: &str, cx: &CodegenCx<'gcc, 'tcx>) -> Function<'gcc> {
+    if let Some(func) = hint_intrinsic(name, cx) {
+        return func;
+    }
+
     match name {
         "llvm.x86.xgetbv" => {
             let gcc_name = "__builtin_trap";
@@ -181,8 +306,120 @@ pub fn intrinsic<'gcc, 'tcx>(name: &str, cx: &CodegenCx<'gcc, 'tcx>) -> Function
     }
 }
 
+/// Translate the name of an overloaded AArch64 NEON LLVM intrinsic, e.g.
+/// `llvm.aarch64.neon.smax.v8i8`, to the name of the GCC builtin, e.g. `__builtin_aarch64_smaxv8qi`.
+// NOTE: GCC names the vector types of its builtins after their machine mode and suffixes the
+// builtins operating on unsigned vectors with `_uuu`. Only the operation and mode pairs that GCC
+// provides are listed here.
+#[cfg(feature="master")]
+fn neon_builtin_name(name: &str) -> Option<&'static str> {
+    let gcc_name =
+        match name {
+            "llvm.aarch64.neon.smax.v8i8" => "__builtin_aarch64_smaxv8qi",
+            "llvm.aarch64.neon.smax.v16i8" => "__builtin_aarch64_smaxv16qi",
+            "llvm.aarch64.neon.smax.v4i16" => "__builtin_aarch64_smaxv4hi",
+            "llvm.aarch64.neon.smax.v8i16" => "__builtin_aarch64_smaxv8hi",
+            "llvm.aarch64.neon.smax.v2i32" => "__builtin_aarch64_smaxv2si",
+            "llvm.aarch64.neon.smax.v4i32" => "__builtin_aarch64_smaxv4si",
+            "llvm.aarch64.neon.smin.v8i8" => "__builtin_aarch64_sminv8qi",
+            "llvm.aarch64.neon.smin.v16i8" => "__builtin_aarch64_sminv16qi",
+            "llvm.aarch64.neon.smin.v4i16" => "__builtin_aarch64_sminv4hi",
+            "llvm.aarch64.neon.smin.v8i16" => "__builtin_aarch64_sminv8hi",
+            "llvm.aarch64.neon.smin.v2i32" => "__builtin_aarch64_sminv2si",
+            "llvm.aarch64.neon.smin.v4i32" => "__builtin_aarch64_sminv4si",
+            "llvm.aarch64.neon.shadd.v8i8" => "__builtin_aarch64_shaddv8qi",
+            "llvm.aarch64.neon.shadd.v16i8" => "__builtin_aarch64_shaddv16qi",
+            "llvm.aarch64.neon.shadd.v4i16" => "__builtin_aarch64_shaddv4hi",
+            "llvm.aarch64.neon.shadd.v8i16" => "__builtin_aarch64_shaddv8hi",
+            "llvm.aarch64.neon.shadd.v2i32" => "__builtin_aarch64_shaddv2si",
+            "llvm.aarch64.neon.shadd.v4i32" => "__builtin_aarch64_shaddv4si",
+            "llvm.aarch64.neon.srhadd.v8i8" => "__builtin_aarch64_srhaddv8qi",
+            "llvm.aarch64.neon.srhadd.v16i8" => "__builtin_aarch64_srhaddv16qi",
+            "llvm.aarch64.neon.srhadd.v4i16" => "__builtin_aarch64_srhaddv4hi",
+            "llvm.aarch64.neon.srhadd.v8i16" => "__builtin_aarch64_srhaddv8hi",
+            "llvm.aarch64.neon.srhadd.v2i32" => "__builtin_aarch64_srhaddv2si",
+            "llvm.aarch64.neon.srhadd.v4i32" => "__builtin_aarch64_srhaddv4si",
+            "llvm.aarch64.neon.umax.v8i8" => "__builtin_aarch64_umaxv8qi_uuu",
+            "llvm.aarch64.neon.umax.v16i8" => "__builtin_aarch64_umaxv16qi_uuu",
+            "llvm.aarch64.neon.umax.v4i16" => "__builtin_aarch64_umaxv4hi_uuu",
+            "llvm.aarch64.neon.umax.v8i16" => "__builtin_aarch64_umaxv8hi_uuu",
+            "llvm.aarch64.neon.umax.v2i32" => "__builtin_aarch64_umaxv2si_uuu",
+            "llvm.aarch64.neon.umax.v4i32" => "__builtin_aarch64_umaxv4si_uuu",
+            "llvm.aarch64.neon.umin.v8i8" => "__builtin_aarch64_uminv8qi_uuu",
+            "llvm.aarch64.neon.umin.v16i8" => "__builtin_aarch64_uminv16qi_uuu",
+            "llvm.aarch64.neon.umin.v4i16" => "__builtin_aarch64_uminv4hi_uuu",
+            "llvm.aarch64.neon.umin.v8i16" => "__builtin_aarch64_uminv8hi_uuu",
+            "llvm.aarch64.neon.umin.v2i32" => "__builtin_aarch64_uminv2si_uuu",
+            "llvm.aarch64.neon.umin.v4i32" => "__builtin_aarch64_uminv4si_uuu",
+            "llvm.aarch64.neon.uhadd.v8i8" => "__builtin_aarch64_uhaddv8qi_uuu",
+            "llvm.aarch64.neon.uhadd.v16i8" => "__builtin_aarch64_uhaddv16qi_uuu",
+            "llvm.aarch64.neon.uhadd.v4i16" => "__builtin_aarch64_uhaddv4hi_uuu",
+            "llvm.aarch64.neon.uhadd.v8i16" => "__builtin_aarch64_uhaddv8hi_uuu",
+            "llvm.aarch64.neon.uhadd.v2i32" => "__builtin_aarch64_uhaddv2si_uuu",
+            "llvm.aarch64.neon.uhadd.v4i32" => "__builtin_aarch64_uhaddv4si_uuu",
+            "llvm.aarch64.neon.urhadd.v8i8" => "__builtin_aarch64_urhaddv8qi_uuu",
+            "llvm.aarch64.neon.urhadd.v16i8" => "__builtin_aarch64_urhaddv16qi_uuu",
+            "llvm.aarch64.neon.urhadd.v4i16" => "__builtin_aarch64_urhaddv4hi_uuu",
+            "llvm.aarch64.neon.urhadd.v8i16" => "__builtin_aarch64_urhaddv8hi_uuu",
+            "llvm.aarch64.neon.urhadd.v2i32" => "__builtin_aarch64_urhaddv2si_uuu",
+            "llvm.aarch64.neon.urhadd.v4i32" => "__builtin_aarch64_urhaddv4si_uuu",
+            "llvm.aarch64.neon.sqadd.v8i8" => "__builtin_aarch64_sqaddv8qi",
+            "llvm.aarch64.neon.sqadd.v16i8" => "__builtin_aarch64_sqaddv16qi",
+            "llvm.aarch64.neon.sqadd.v4i16" => "__builtin_aarch64_sqaddv4hi",
+            "llvm.aarch64.neon.sqadd.v8i16" => "__builtin_aarch64_sqaddv8hi",
+            "llvm.aarch64.neon.sqadd.v2i32" => "__builtin_aarch64_sqaddv2si",
+            "llvm.aarch64.neon.sqadd.v4i32" => "__builtin_aarch64_sqaddv4si",
+            "llvm.aarch64.neon.sqadd.v2i64" => "__builtin_aarch64_sqaddv2di",
+            "llvm.aarch64.neon.sqsub.v8i8" => "__builtin_aarch64_sqsubv8qi",
+            "llvm.aarch64.neon.sqsub.v16i8" => "__builtin_aarch64_sqsubv16qi",
+            "llvm.aarch64.neon.sqsub.v4i16" => "__builtin_aarch64_sqsubv4hi",
+            "llvm.aarch64.neon.sqsub.v8i16" => "__builtin_aarch64_sqsubv8hi",
+            "llvm.aarch64.neon.sqsub.v2i32" => "__builtin_aarch64_sqsubv2si",
+            "llvm.aarch64.neon.sqsub.v4i32" => "__builtin_aarch64_sqsubv4si",
+            "llvm.aarch64.neon.sqsub.v2i64" => "__builtin_aarch64_sqsubv2di",
+            "llvm.aarch64.neon.uqadd.v8i8" => "__builtin_aarch64_uqaddv8qi_uuu",
+            "llvm.aarch64.neon.uqadd.v16i8" => "__builtin_aarch64_uqaddv16qi_uuu",
+            "llvm.aarch64.neon.uqadd.v4i16" => "__builtin_aarch64_uqaddv4hi_uuu",
+            "llvm.aarch64.neon.uqadd.v8i16" => "__builtin_aarch64_uqaddv8hi_uuu",
+            "llvm.aarch64.neon.uqadd.v2i32" => "__builtin_aarch64_uqaddv2si_uuu",
+            "llvm.aarch64.neon.uqadd.v4i32" => "__builtin_aarch64_uqaddv4si_uuu",
+            "llvm.aarch64.neon.uqadd.v2i64" => "__builtin_aarch64_uqaddv2di_uuu",
+            "llvm.aarch64.neon.uqsub.v8i8" => "__builtin_aarch64_uqsubv8qi_uuu",
+            "llvm.aarch64.neon.uqsub.v16i8" => "__builtin_aarch64_uqsubv16qi_uuu",
+            "llvm.aarch64.neon.uqsub.v4i16" => "__builtin_aarch64_uqsubv4hi_uuu",
+            "llvm.aarch64.neon.uqsub.v8i16" => "__builtin_aarch64_uqsubv8hi_uuu",
+            "llvm.aarch64.neon.uqsub.v2i32" => "__builtin_aarch64_uqsubv2si_uuu",
+            "llvm.aarch64.neon.uqsub.v4i32" => "__builtin_aarch64_uqsubv4si_uuu",
+            "llvm.aarch64.neon.uqsub.v2i64" => "__builtin_aarch64_uqsubv2di_uuu",
+            "llvm.aarch64.neon.addp.v8i8" => "__builtin_aarch64_addpv8qi",
+            "llvm.aarch64.neon.addp.v4i16" => "__builtin_aarch64_addpv4hi",
+            "llvm.aarch64.neon.addp.v2i32" => "__builtin_aarch64_addpv2si",
+            "llvm.aarch64.neon.abs.v8i8" => "__builtin_aarch64_absv8qi",
+            "llvm.aarch64.neon.abs.v16i8" => "__builtin_aarch64_absv16qi",
+            "llvm.aarch64.neon.abs.v4i16" => "__builtin_aarch64_absv4hi",
+            "llvm.aarch64.neon.abs.v8i16" => "__builtin_aarch64_absv8hi",
+            "llvm.aarch64.neon.abs.v2i32" => "__builtin_aarch64_absv2si",
+            "llvm.aarch64.neon.abs.v4i32" => "__builtin_aarch64_absv4si",
+            "llvm.aarch64.neon.abs.v2i64" => "__builtin_aarch64_absv2di",
+            "llvm.aarch64.neon.tbl1.v8i8" => "__builtin_aarch64_qtbl1v8qi",
+            "llvm.aarch64.neon.tbl1.v16i8" => "__builtin_aarch64_qtbl1v16qi",
+            _ => return None,
+        };
+    Some(gcc_name)
+}
+
 #[cfg(feature="master")]
 pub fn intrinsic<'gcc, 'tcx>(name: &str, cx: &CodegenCx<'gcc, 'tcx>) -> Function<'gcc> {
+    if let Some(func) = hint_intrinsic(name, cx) {
+        return func;
+    }
+
+    if let Some(gcc_name) = neon_builtin_name(name) {
+        let func = cx.context.get_target_builtin_function(gcc_name);
+        cx.functions.borrow_mut().insert(gcc_name.to_string(), func);
+        return func;
+    }
+
     let gcc_name = match name {
         "llvm.x86.xgetbv" => "__builtin_ia32_xgetbv",
         // NOTE: this doc specifies the equivalent GCC builtins: http://huonw.github.io/llvmint/llvmint/x86/index.html
@@ -223,6 +460,26 @@ pub fn intrinsic<'gcc, 'tcx>(name: &str, cx: &CodegenCx<'gcc, 'tcx>) -> Function
         "llvm.x86.avx512.vfmadd.pd.512" => "__builtin_ia32_vfmaddpd512_mask",
 
         // The above doc points to unknown builtins for the following, so override them:
+        "llvm.x86.avx512.permvar.si.512" => "__builtin_ia32_permvarsi512_mask",
+        "llvm.x86.avx512.permvar.di.512" => "__builtin_ia32_permvardi512_mask",
+        "llvm.x86.avx512.permvar.sf.512" => "__builtin_ia32_permvarsf512_mask",
+        "llvm.x86.avx512.permvar.df.512" => "__builtin_ia32_permvardf512_mask",
+        "llvm.x86.avx512.permvar.di.256" => "__builtin_ia32_permvardi256_mask",
+        "llvm.x86.avx512.permvar.df.256" => "__builtin_ia32_permvardf256_mask",
+        "llvm.x86.avx512.vpermi2var.d.512" => "__builtin_ia32_vpermi2vard512_mask",
+        "llvm.x86.avx512.vpermi2var.q.512" => "__builtin_ia32_vpermi2varq512_mask",
+        "llvm.x86.avx512.vpermi2var.ps.512" => "__builtin_ia32_vpermi2varps512_mask",
+        "llvm.x86.avx512.vpermi2var.pd.512" => "__builtin_ia32_vpermi2varpd512_mask",
+        "llvm.x86.addcarry.32" => "__builtin_ia32_addcarryx_u32",
+        "llvm.x86.addcarry.64" => "__builtin_ia32_addcarryx_u64",
+        "llvm.x86.subborrow.32" => "__builtin_ia32_sbb_u32",
+        "llvm.x86.subborrow.64" => "__builtin_ia32_sbb_u64",
+        "llvm.x86.rdrand.16" => "__builtin_ia32_rdrand16_step",
+        "llvm.x86.rdrand.32" => "__builtin_ia32_rdrand32_step",
+        "llvm.x86.rdrand.64" => "__builtin_ia32_rdrand64_step",
+        "llvm.x86.rdseed.16" => "__builtin_ia32_rdseed_hi_step",
+        "llvm.x86.rdseed.32" => "__builtin_ia32_rdseed_si_step",
+        "llvm.x86.rdseed.64" => "__builtin_ia32_rdseed_di_step",
         "llvm.x86.avx2.gather.d.d" => "__builtin_ia32_gathersiv4si",
         "llvm.x86.avx2.gather.d.d.256" => "__builtin_ia32_gathersiv8si",
         "llvm.x86.avx2.gather.d.ps" => "__builtin_ia32_gathersiv4sf",
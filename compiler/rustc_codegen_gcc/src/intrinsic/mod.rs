@@ -7,7 +7,7 @@
 use rustc_codegen_ssa::common::IntPredicate;
 use rustc_codegen_ssa::mir::operand::{OperandRef, OperandValue};
 use rustc_codegen_ssa::mir::place::PlaceRef;
-use rustc_codegen_ssa::traits::{ArgAbiMethods, BaseTypeMethods, BuilderMethods, ConstMethods, IntrinsicCallMethods};
+use rustc_codegen_ssa::traits::{AbiBuilderMethods, ArgAbiMethods, BaseTypeMethods, BuilderMethods, ConstMethods, IntrinsicCallMethods};
 use rustc_middle::bug;
 use rustc_middle::ty::{self, Instance, Ty};
 use rustc_middle::ty::layout::LayoutOf;
@@ -20,7 +20,7 @@
 use crate::builder::Builder;
 use crate::common::{SignType, TypeReflection};
 use crate::context::CodegenCx;
-use crate::errors::InvalidMonomorphizationBasicInteger;
+use crate::errors::{CVariadicIntrinsicNotSupported, CVariadicNotSupported, InvalidMonomorphizationBasicInteger};
 use crate::type_of::LayoutGccExt;
 use crate::intrinsic::simd::generic_simd_intrinsic;
 
@@ -50,10 +50,6 @@ fn get_simple_intrinsic<'gcc, 'tcx>(cx: &CodegenCx<'gcc, 'tcx>, name: Symbol) ->
         sym::fmaf64 => "fma",
         sym::fabsf32 => "fabsf",
         sym::fabsf64 => "fabs",
-        sym::minnumf32 => "fminf",
-        sym::minnumf64 => "fmin",
-        sym::maxnumf32 => "fmaxf",
-        sym::maxnumf64 => "fmax",
         sym::copysignf32 => "copysignf",
         sym::copysignf64 => "copysign",
         sym::floorf32 => "floorf",
@@ -68,7 +64,7 @@ fn get_simple_intrinsic<'gcc, 'tcx>(cx: &CodegenCx<'gcc, 'tcx>, name: Symbol) ->
         sym::nearbyintf64 => "nearbyint",
         sym::roundf32 => "roundf",
         sym::roundf64 => "round",
-        sym::abort => "abort",
+        sym::abort => "__builtin_trap",
         _ => return None,
     };
     Some(cx.context.get_builtin_function(&gcc_name))
@@ -102,6 +98,12 @@ fn codegen_intrinsic_call(&mut self, instance: Instance<'tcx>, fn_abi: &FnAbi<'t
                     let func = unsafe { std::mem::transmute(simple.expect("simple")) };
                     self.call(self.type_void(), func, &args.iter().map(|arg| arg.immediate()).collect::<Vec<_>>(), None)
                 },
+                sym::minnumf32 | sym::minnumf64 => {
+                    self.float_min_max(args[0].immediate(), args[1].immediate(), true)
+                }
+                sym::maxnumf32 | sym::maxnumf64 => {
+                    self.float_min_max(args[0].immediate(), args[1].immediate(), false)
+                }
                 sym::likely => {
                     self.expect(args[0].immediate(), true)
                 }
@@ -119,24 +121,49 @@ fn codegen_intrinsic_call(&mut self, instance: Instance<'tcx>, fn_abi: &FnAbi<'t
                     return;
                 }
                 sym::breakpoint => {
-                    unimplemented!();
-                }
-                sym::va_copy => {
-                    unimplemented!();
+                    let instruction =
+                        match &*self.sess().target.arch {
+                            "x86" | "x86_64" => "int3",
+                            "aarch64" => "brk #0xf000",
+                            "arm" => "bkpt",
+                            "riscv32" | "riscv64" => "ebreak",
+                            // NOTE: fallback on a trap for the other architectures.
+                            _ => {
+                                let func = self.context.get_builtin_function("__builtin_trap");
+                                self.llbb().add_eval(self.location, self.context.new_call(self.location, func, &[]));
+                                return;
+                            },
+                        };
+                    let extended_asm = self.llbb().add_extended_asm(self.location, instruction);
+                    extended_asm.add_clobber("memory");
+                    extended_asm.set_volatile_flag(true);
+                    return;
                 }
-                sym::va_arg => {
-                    unimplemented!();
+                sym::va_copy | sym::va_arg => {
+                    self.sess().emit_fatal(CVariadicIntrinsicNotSupported { span, name });
                 }
 
                 sym::volatile_load | sym::unaligned_volatile_load => {
                     let tp_ty = substs.type_at(0);
-                    let mut ptr = args[0].immediate();
-                    if let PassMode::Cast(ty, _) = &fn_abi.ret.mode {
-                        ptr = self.pointercast(ptr, self.type_ptr_to(ty.gcc_type(self)));
-                    }
-                    let load = self.volatile_load(ptr.get_type(), ptr);
-                    // TODO(antoyo): set alignment.
-                    self.to_immediate(load, self.layout_of(tp_ty))
+                    let layout = self.layout_of(tp_ty);
+                    let load_ty =
+                        if let PassMode::Cast(ty, _) = &fn_abi.ret.mode {
+                            ty.gcc_type(self)
+                        }
+                        else {
+                            layout.gcc_type(self, true)
+                        };
+                    // NOTE: libgccjit does not support specifying the alignment on the load, so we
+                    // use an under-aligned type for the unaligned variant.
+                    let load_ty =
+                        if name == sym::unaligned_volatile_load {
+                            load_ty.get_aligned(1)
+                        }
+                        else {
+                            load_ty
+                        };
+                    let load = self.volatile_load(load_ty, args[0].immediate());
+                    self.to_immediate(load, layout)
                 }
                 sym::volatile_store => {
                     let dst = args[0].deref(self.cx());
@@ -175,14 +202,14 @@ fn codegen_intrinsic_call(&mut self, instance: Instance<'tcx>, fn_abi: &FnAbi<'t
                                     let after_block = func.new_block("after");
 
                                     let arg = args[0].immediate();
-                                    let result = func.new_local(None, arg.get_type(), "zeros");
+                                    let result = func.new_local(self.location, arg.get_type(), "zeros");
                                     let zero = self.cx.gcc_zero(arg.get_type());
                                     let cond = self.gcc_icmp(IntPredicate::IntEQ, arg, zero);
-                                    self.llbb().end_with_conditional(None, cond, then_block, else_block);
+                                    self.llbb().end_with_conditional(self.location, cond, then_block, else_block);
 
                                     let zero_result = self.cx.gcc_uint(arg.get_type(), width);
-                                    then_block.add_assignment(None, result, zero_result);
-                                    then_block.end_with_jump(None, after_block);
+                                    then_block.add_assignment(self.location, result, zero_result);
+                                    then_block.end_with_jump(self.location, after_block);
 
                                     // NOTE: since jumps were added in a place
                                     // count_leading_zeroes() does not expect, the current block
@@ -195,8 +222,8 @@ fn codegen_intrinsic_call(&mut self, instance: Instance<'tcx>, fn_abi: &FnAbi<'t
                                             sym::cttz => self.count_trailing_zeroes(width, arg),
                                             _ => unreachable!(),
                                         };
-                                    self.llbb().add_assignment(None, result, zeros);
-                                    self.llbb().end_with_jump(None, after_block);
+                                    self.llbb().add_assignment(self.location, result, zeros);
+                                    self.llbb().end_with_jump(self.location, after_block);
 
                                     // NOTE: since jumps were added in a place rustc does not
                                     // expect, the current block in the state need to be updated.
@@ -221,18 +248,8 @@ fn codegen_intrinsic_call(&mut self, instance: Instance<'tcx>, fn_abi: &FnAbi<'t
                                 },
                                 sym::bitreverse => self.bit_reverse(width, args[0].immediate()),
                                 sym::rotate_left | sym::rotate_right => {
-                                    // TODO(antoyo): implement using algorithm from:
-                                    // https://blog.regehr.org/archives/1063
-                                    // for other platforms.
                                     let is_left = name == sym::rotate_left;
-                                    let val = args[0].immediate();
-                                    let raw_shift = args[1].immediate();
-                                    if is_left {
-                                        self.rotate_left(val, raw_shift, width)
-                                    }
-                                    else {
-                                        self.rotate_right(val, raw_shift, width)
-                                    }
+                                    self.rotate(args[0].immediate(), args[1].immediate(), width, is_left)
                                 },
                                 sym::saturating_add => {
                                     self.saturating_add(args[0].immediate(), args[1].immediate(), signed, width)
@@ -253,7 +270,7 @@ fn codegen_intrinsic_call(&mut self, instance: Instance<'tcx>, fn_abi: &FnAbi<'t
                     use rustc_target::abi::Abi::*;
                     let tp_ty = substs.type_at(0);
                     let layout = self.layout_of(tp_ty).layout;
-                    let _use_integer_compare = match layout.abi() {
+                    let use_integer_compare = match layout.abi() {
                         Scalar(_) | ScalarPair(_, _) => true,
                         Uninhabited | Vector { .. } => false,
                         Aggregate { .. } => {
@@ -266,40 +283,75 @@ fn codegen_intrinsic_call(&mut self, instance: Instance<'tcx>, fn_abi: &FnAbi<'t
 
                     let a = args[0].immediate();
                     let b = args[1].immediate();
-                    if layout.size().bytes() == 0 {
+                    let size = layout.size();
+                    // NOTE: GCC has no integer type for sizes that are not a power of two (e.g.
+                    // 96 bits for [i32; 3]), so those use memcmp.
+                    let integer_ty =
+                        if use_integer_compare && size.bytes().is_power_of_two() {
+                            Some(self.cx.type_ix(size.bits())).filter(|&typ| self.is_native_int_type(typ))
+                        }
+                        else {
+                            None
+                        };
+                    if size.bytes() == 0 {
                         self.const_bool(true)
                     }
-                    /*else if use_integer_compare {
-                        let integer_ty = self.type_ix(layout.size.bits()); // FIXME(antoyo): LLVM creates an integer of 96 bits for [i32; 3], but gcc doesn't support this, so it creates an integer of 128 bits.
+                    else if let Some(integer_ty) = integer_ty {
+                        // NOTE: the values might be less aligned than the integer type.
+                        let integer_ty = integer_ty.get_aligned(layout.align().abi.bytes());
                         let ptr_ty = self.type_ptr_to(integer_ty);
                         let a_ptr = self.bitcast(a, ptr_ty);
-                        let a_val = self.load(integer_ty, a_ptr, layout.align.abi);
+                        let a_val = self.load(integer_ty, a_ptr, layout.align().abi);
                         let b_ptr = self.bitcast(b, ptr_ty);
-                        let b_val = self.load(integer_ty, b_ptr, layout.align.abi);
+                        let b_val = self.load(integer_ty, b_ptr, layout.align().abi);
                         self.icmp(IntPredicate::IntEQ, a_val, b_val)
-                    }*/
+                    }
                     else {
                         let void_ptr_type = self.context.new_type::<*const ()>();
                         let a_ptr = self.bitcast(a, void_ptr_type);
                         let b_ptr = self.bitcast(b, void_ptr_type);
-                        let n = self.context.new_cast(None, self.const_usize(layout.size().bytes()), self.sizet_type);
-                        let builtin = self.context.get_builtin_function("memcmp");
-                        let cmp = self.context.new_call(None, builtin, &[a_ptr, b_ptr, n]);
+                        let n = self.context.new_cast(self.location, self.const_usize(layout.size().bytes()), self.sizet_type);
+                        let builtin = self.context.get_builtin_function("__builtin_memcmp");
+                        let cmp = self.context.new_call(self.location, builtin, &[a_ptr, b_ptr, n]);
                         self.icmp(IntPredicate::IntEQ, cmp, self.const_i32(0))
                     }
                 }
 
                 sym::black_box => {
-                    args[0].val.store(self, result);
-
-                    let block = self.llbb();
-                    let extended_asm = block.add_extended_asm(None, "");
-                    extended_asm.add_input_operand(None, "r", result.llval);
-                    extended_asm.add_clobber("memory");
-                    extended_asm.set_volatile_flag(true);
+                    // NOTE: integers and pointers that fit in a register are passed through an
+                    // empty asm with a "+r" constraint to avoid a round-trip through the stack.
+                    let register_value =
+                        match args[0].val {
+                            OperandValue::Immediate(value) => {
+                                let value_type = value.get_type();
+                                let fits_in_register = value_type.get_size() as u64 <= self.tcx.data_layout.pointer_size.bytes();
+                                if fits_in_register && (value_type.is_integral() || value_type.get_pointee().is_some()) {
+                                    Some(value)
+                                }
+                                else {
+                                    None
+                                }
+                            },
+                            _ => None,
+                        };
 
-                    // We have copied the value to `result` already.
-                    return;
+                    match register_value {
+                        Some(value) => {
+                            let func = self.current_func();
+                            let variable = func.new_local(self.location, value.get_type(), "black_box");
+                            self.llbb().add_assignment(self.location, variable, value);
+                            let extended_asm = self.llbb().add_extended_asm(self.location, "");
+                            extended_asm.add_output_operand(self.location, "+r", variable);
+                            extended_asm.add_clobber("memory");
+                            extended_asm.set_volatile_flag(true);
+                            variable.to_rvalue()
+                        },
+                        None => {
+                            self.black_box_in_memory(args[0], result);
+                            // We have copied the value to `result` already.
+                            return;
+                        },
+                    }
                 }
 
                 sym::ptr_mask => {
@@ -339,21 +391,23 @@ fn codegen_intrinsic_call(&mut self, instance: Instance<'tcx>, fn_abi: &FnAbi<'t
     }
 
     fn abort(&mut self) {
-        let func = self.context.get_builtin_function("abort");
+        let func = self.context.get_builtin_function("__builtin_trap");
         let func: RValue<'gcc> = unsafe { std::mem::transmute(func) };
         self.call(self.type_void(), func, &[], None);
     }
 
     fn assume(&mut self, value: Self::Value) {
         // TODO(antoyo): switch to assume when it exists.
-        // Or use something like this:
-        // #define __assume(cond) do { if (!(cond)) __builtin_unreachable(); } while (0)
-        self.expect(value, true);
+        self.assume_condition(value);
     }
 
-    fn expect(&mut self, cond: Self::Value, _expected: bool) -> Self::Value {
-        // TODO(antoyo)
-        cond
+    fn expect(&mut self, cond: Self::Value, expected: bool) -> Self::Value {
+        let expect = self.context.get_builtin_function("__builtin_expect");
+        let cond = self.context.new_cast(self.location, cond, self.long_type);
+        let expected = self.context.new_rvalue_from_long(self.long_type, expected as i64);
+        let result = self.context.new_call(self.location, expect, &[cond, expected]);
+        let zero = self.context.new_rvalue_zero(self.long_type);
+        self.context.new_comparison(self.location, ComparisonOp::NotEquals, result, zero)
     }
 
     fn type_test(&mut self, _pointer: Self::Value, _typeid: Self::Value) -> Self::Value {
@@ -372,11 +426,12 @@ fn type_checked_load(
     }
 
     fn va_start(&mut self, _va_list: RValue<'gcc>) -> RValue<'gcc> {
-        unimplemented!();
+        // NOTE: this is called in the prologue of C-variadic functions defined in Rust.
+        self.sess().emit_fatal(CVariadicNotSupported);
     }
 
     fn va_end(&mut self, _va_list: RValue<'gcc>) -> RValue<'gcc> {
-        unimplemented!();
+        self.sess().emit_fatal(CVariadicNotSupported);
     }
 }
 
@@ -473,10 +528,12 @@ fn store(&self, bx: &mut Builder<'_, 'gcc, 'tcx>, val: RValue<'gcc>, dst: PlaceR
     }
 
     fn store_fn_arg<'a>(&self, bx: &mut Builder<'a, 'gcc, 'tcx>, idx: &mut usize, dst: PlaceRef<'tcx, RValue<'gcc>>) {
+        // NOTE: use AbiBuilderMethods::get_param so that the arguments passed by value on the
+        // stack (byval) give the address of their copy instead of their value.
         let mut next = || {
-            let val = bx.current_func().get_param(*idx as i32);
+            let val = bx.get_param(*idx);
             *idx += 1;
-            val.to_rvalue()
+            val
         };
         match self.mode {
             PassMode::Ignore => {},
@@ -714,9 +771,9 @@ fn count_leading_zeroes(&mut self, width: u64, arg: RValue<'gcc>) -> RValue<'gcc
             }
             else if width == 128 {
                 // Algorithm from: https://stackoverflow.com/a/28433850/389119
-                let array_type = self.context.new_array_type(None, arg_type, 3);
+                let array_type = self.context.new_array_type(self.location, arg_type, 3);
                 let result = self.current_func()
-                    .new_local(None, array_type, "count_loading_zeroes_results");
+                    .new_local(self.location, array_type, "count_loading_zeroes_results");
 
                 let sixty_four = self.const_uint(arg_type, 64);
                 let shift = self.lshr(arg, sixty_four);
@@ -729,47 +786,47 @@ fn count_leading_zeroes(&mut self, width: u64, arg: RValue<'gcc>) -> RValue<'gcc
 
                 let clzll = self.context.get_builtin_function("__builtin_clzll");
 
-                let first_elem = self.context.new_array_access(None, result, zero);
-                let first_value = self.gcc_int_cast(self.context.new_call(None, clzll, &[high]), arg_type);
+                let first_elem = self.context.new_array_access(self.location, result, zero);
+                let first_value = self.gcc_int_cast(self.context.new_call(self.location, clzll, &[high]), arg_type);
                 self.llbb()
-                    .add_assignment(None, first_elem, first_value);
+                    .add_assignment(self.location, first_elem, first_value);
 
-                let second_elem = self.context.new_array_access(None, result, one);
-                let cast = self.gcc_int_cast(self.context.new_call(None, clzll, &[low]), arg_type);
+                let second_elem = self.context.new_array_access(self.location, result, one);
+                let cast = self.gcc_int_cast(self.context.new_call(self.location, clzll, &[low]), arg_type);
                 let second_value = self.add(cast, sixty_four);
                 self.llbb()
-                    .add_assignment(None, second_elem, second_value);
+                    .add_assignment(self.location, second_elem, second_value);
 
-                let third_elem = self.context.new_array_access(None, result, two);
+                let third_elem = self.context.new_array_access(self.location, result, two);
                 let third_value = self.const_uint(arg_type, 128);
                 self.llbb()
-                    .add_assignment(None, third_elem, third_value);
+                    .add_assignment(self.location, third_elem, third_value);
 
-                let not_high = self.context.new_unary_op(None, UnaryOp::LogicalNegate, self.u64_type, high);
-                let not_low = self.context.new_unary_op(None, UnaryOp::LogicalNegate, self.u64_type, low);
+                let not_high = self.context.new_unary_op(self.location, UnaryOp::LogicalNegate, self.u64_type, high);
+                let not_low = self.context.new_unary_op(self.location, UnaryOp::LogicalNegate, self.u64_type, low);
                 let not_low_and_not_high = not_low & not_high;
                 let index = not_high + not_low_and_not_high;
                 // NOTE: the following cast is necessary to avoid a GIMPLE verification failure in
                 // gcc.
                 // TODO(antoyo): do the correct verification in libgccjit to avoid an error at the
                 // compilation stage.
-                let index = self.context.new_cast(None, index, self.i32_type);
+                let index = self.context.new_cast(self.location, index, self.i32_type);
 
-                let res = self.context.new_array_access(None, result, index);
+                let res = self.context.new_array_access(self.location, result, index);
 
                 return self.gcc_int_cast(res.to_rvalue(), arg_type);
             }
             else {
                 let count_leading_zeroes = self.context.get_builtin_function("__builtin_clzll");
-                let arg = self.context.new_cast(None, arg, self.ulonglong_type);
+                let arg = self.context.new_cast(self.location, arg, self.ulonglong_type);
                 let diff = self.ulonglong_type.get_size() as i64 - arg_type.get_size() as i64;
                 let diff = self.context.new_rvalue_from_long(self.int_type, diff * 8);
-                let res = self.context.new_call(None, count_leading_zeroes, &[arg]) - diff;
-                return self.context.new_cast(None, res, arg_type);
+                let res = self.context.new_call(self.location, count_leading_zeroes, &[arg]) - diff;
+                return self.context.new_cast(self.location, res, arg_type);
             };
         let count_leading_zeroes = self.context.get_builtin_function(count_leading_zeroes);
-        let res = self.context.new_call(None, count_leading_zeroes, &[arg]);
-        self.context.new_cast(None, res, arg_type)
+        let res = self.context.new_call(self.location, count_leading_zeroes, &[arg]);
+        self.context.new_cast(self.location, res, arg_type)
     }
 
     fn count_trailing_zeroes(&mut self, _width: u64, arg: RValue<'gcc>) -> RValue<'gcc> {
@@ -798,9 +855,9 @@ fn count_trailing_zeroes(&mut self, _width: u64, arg: RValue<'gcc>) -> RValue<'g
             }
             else if arg_type.is_u128(&self.cx) {
                 // Adapted from the algorithm to count leading zeroes from: https://stackoverflow.com/a/28433850/389119
-                let array_type = self.context.new_array_type(None, arg_type, 3);
+                let array_type = self.context.new_array_type(self.location, arg_type, 3);
                 let result = self.current_func()
-                    .new_local(None, array_type, "count_loading_zeroes_results");
+                    .new_local(self.location, array_type, "count_loading_zeroes_results");
 
                 let sixty_four = self.gcc_int(arg_type, 64);
                 let shift = self.gcc_lshr(arg, sixty_four);
@@ -813,58 +870,58 @@ fn count_trailing_zeroes(&mut self, _width: u64, arg: RValue<'gcc>) -> RValue<'g
 
                 let ctzll = self.context.get_builtin_function("__builtin_ctzll");
 
-                let first_elem = self.context.new_array_access(None, result, zero);
-                let first_value = self.gcc_int_cast(self.context.new_call(None, ctzll, &[low]), arg_type);
+                let first_elem = self.context.new_array_access(self.location, result, zero);
+                let first_value = self.gcc_int_cast(self.context.new_call(self.location, ctzll, &[low]), arg_type);
                 self.llbb()
-                    .add_assignment(None, first_elem, first_value);
+                    .add_assignment(self.location, first_elem, first_value);
 
-                let second_elem = self.context.new_array_access(None, result, one);
-                let second_value = self.gcc_add(self.gcc_int_cast(self.context.new_call(None, ctzll, &[high]), arg_type), sixty_four);
+                let second_elem = self.context.new_array_access(self.location, result, one);
+                let second_value = self.gcc_add(self.gcc_int_cast(self.context.new_call(self.location, ctzll, &[high]), arg_type), sixty_four);
                 self.llbb()
-                    .add_assignment(None, second_elem, second_value);
+                    .add_assignment(self.location, second_elem, second_value);
 
-                let third_elem = self.context.new_array_access(None, result, two);
+                let third_elem = self.context.new_array_access(self.location, result, two);
                 let third_value = self.gcc_int(arg_type, 128);
                 self.llbb()
-                    .add_assignment(None, third_elem, third_value);
+                    .add_assignment(self.location, third_elem, third_value);
 
-                let not_low = self.context.new_unary_op(None, UnaryOp::LogicalNegate, self.u64_type, low);
-                let not_high = self.context.new_unary_op(None, UnaryOp::LogicalNegate, self.u64_type, high);
+                let not_low = self.context.new_unary_op(self.location, UnaryOp::LogicalNegate, self.u64_type, low);
+                let not_high = self.context.new_unary_op(self.location, UnaryOp::LogicalNegate, self.u64_type, high);
                 let not_low_and_not_high = not_low & not_high;
                 let index = not_low + not_low_and_not_high;
                 // NOTE: the following cast is necessary to avoid a GIMPLE verification failure in
                 // gcc.
                 // TODO(antoyo): do the correct verification in libgccjit to avoid an error at the
                 // compilation stage.
-                let index = self.context.new_cast(None, index, self.i32_type);
+                let index = self.context.new_cast(self.location, index, self.i32_type);
 
-                let res = self.context.new_array_access(None, result, index);
+                let res = self.context.new_array_access(self.location, result, index);
 
                 return self.gcc_int_cast(res.to_rvalue(), result_type);
             }
             else {
                 let count_trailing_zeroes = self.context.get_builtin_function("__builtin_ctzll");
                 let arg_size = arg_type.get_size();
-                let casted_arg = self.context.new_cast(None, arg, self.ulonglong_type);
+                let casted_arg = self.context.new_cast(self.location, arg, self.ulonglong_type);
                 let byte_diff = self.ulonglong_type.get_size() as i64 - arg_size as i64;
                 let diff = self.context.new_rvalue_from_long(self.int_type, byte_diff * 8);
                 let mask = self.context.new_rvalue_from_long(arg_type, -1); // To get the value with all bits set.
-                let masked = mask & self.context.new_unary_op(None, UnaryOp::BitwiseNegate, arg_type, arg);
-                let cond = self.context.new_comparison(None, ComparisonOp::Equals, masked, mask);
-                let diff = diff * self.context.new_cast(None, cond, self.int_type);
-                let res = self.context.new_call(None, count_trailing_zeroes, &[casted_arg]) - diff;
-                return self.context.new_cast(None, res, result_type);
+                let masked = mask & self.context.new_unary_op(self.location, UnaryOp::BitwiseNegate, arg_type, arg);
+                let cond = self.context.new_comparison(self.location, ComparisonOp::Equals, masked, mask);
+                let diff = diff * self.context.new_cast(self.location, cond, self.int_type);
+                let res = self.context.new_call(self.location, count_trailing_zeroes, &[casted_arg]) - diff;
+                return self.context.new_cast(self.location, res, result_type);
             };
         let count_trailing_zeroes = self.context.get_builtin_function(count_trailing_zeroes);
         let arg =
             if arg_type != expected_type {
-                self.context.new_cast(None, arg, expected_type)
+                self.context.new_cast(self.location, arg, expected_type)
             }
             else {
                 arg
             };
-        let res = self.context.new_call(None, count_trailing_zeroes, &[arg]);
-        self.context.new_cast(None, res, result_type)
+        let res = self.context.new_call(self.location, count_trailing_zeroes, &[arg]);
+        self.context.new_cast(self.location, res, result_type)
     }
 
     fn pop_count(&mut self, value: RValue<'gcc>) -> RValue<'gcc> {
@@ -887,9 +944,9 @@ fn pop_count(&mut self, value: RValue<'gcc>) -> RValue<'gcc> {
             let sixty_four = self.gcc_int(value_type, 64);
             let right_shift = self.gcc_lshr(value, sixty_four);
             let high = self.gcc_int_cast(right_shift, self.cx.ulonglong_type);
-            let high = self.context.new_call(None, popcount, &[high]);
+            let high = self.context.new_call(self.location, popcount, &[high]);
             let low = self.gcc_int_cast(value, self.cx.ulonglong_type);
-            let low = self.context.new_call(None, popcount, &[low]);
+            let low = self.context.new_call(self.location, popcount, &[low]);
             let res = high + low;
             return self.gcc_int_cast(res, result_type);
         }
@@ -916,7 +973,7 @@ fn pop_count(&mut self, value: RValue<'gcc>) -> RValue<'gcc> {
         let value = left + right;
 
         if value_type.is_u8(&self.cx) {
-            return self.context.new_cast(None, value, result_type);
+            return self.context.new_cast(self.location, value, result_type);
         }
 
         // Fourth step.
@@ -927,7 +984,7 @@ fn pop_count(&mut self, value: RValue<'gcc>) -> RValue<'gcc> {
         let value = left + right;
 
         if value_type.is_u16(&self.cx) {
-            return self.context.new_cast(None, value, result_type);
+            return self.context.new_cast(self.location, value, result_type);
         }
 
         // Fifth step.
@@ -938,7 +995,7 @@ fn pop_count(&mut self, value: RValue<'gcc>) -> RValue<'gcc> {
         let value = left + right;
 
         if value_type.is_u32(&self.cx) {
-            return self.context.new_cast(None, value, result_type);
+            return self.context.new_cast(self.location, value, result_type);
         }
 
         // Sixth step.
@@ -948,37 +1005,70 @@ fn pop_count(&mut self, value: RValue<'gcc>) -> RValue<'gcc> {
         let right = shifted & mask;
         let value = left + right;
 
-        self.context.new_cast(None, value, result_type)
+        self.context.new_cast(self.location, value, result_type)
     }
 
-    // Algorithm from: https://blog.regehr.org/archives/1063
-    fn rotate_left(&mut self, value: RValue<'gcc>, shift: RValue<'gcc>, width: u64) -> RValue<'gcc> {
-        let max = self.const_uint(shift.get_type(), width);
-        let shift = self.urem(shift, max);
-        let lhs = self.shl(value, shift);
-        let result_neg = self.neg(shift);
-        let result_and =
-            self.and(
-                result_neg,
-                self.const_uint(shift.get_type(), width - 1),
-            );
-        let rhs = self.lshr(value, result_and);
-        self.or(lhs, rhs)
+    fn black_box_in_memory(&mut self, arg: OperandRef<'tcx, RValue<'gcc>>, result: PlaceRef<'tcx, RValue<'gcc>>) {
+        arg.val.store(self, result);
+
+        let block = self.llbb();
+        let extended_asm = block.add_extended_asm(self.location, "");
+        extended_asm.add_input_operand(self.location, "r", result.llval);
+        extended_asm.add_clobber("memory");
+        extended_asm.set_volatile_flag(true);
+    }
+
+    /// Lower `minnum`/`maxnum`: if one of the arguments is NaN, the other one is returned and
+    /// -0.0 is considered less than +0.0.
+    /// NOTE: `fmin`/`fmax` are not used because they can return either zero when comparing zeros
+    /// of different signs.
+    fn float_min_max(&mut self, a: RValue<'gcc>, b: RValue<'gcc>, is_min: bool) -> RValue<'gcc> {
+        let float_type = a.get_type();
+        let int_type = self.cx.type_ix(self.cx.float_width(float_type) as u64);
+        let a_bits = self.context.new_bitcast(self.location, a, int_type);
+        let b_bits = self.context.new_bitcast(self.location, b, int_type);
+        // NOTE: when both values are equal, only the sign bit can differ.
+        let (equal_bits, comparison) =
+            if is_min {
+                (self.gcc_or(a_bits, b_bits), ComparisonOp::LessThan)
+            }
+            else {
+                (self.gcc_and(a_bits, b_bits), ComparisonOp::GreaterThan)
+            };
+        let equal_result = self.context.new_bitcast(self.location, equal_bits, float_type);
+
+        let a_is_nan = self.context.new_comparison(self.location, ComparisonOp::NotEquals, a, a);
+        let b_is_nan = self.context.new_comparison(self.location, ComparisonOp::NotEquals, b, b);
+        let is_equal = self.context.new_comparison(self.location, ComparisonOp::Equals, a, b);
+        let is_a = self.context.new_comparison(self.location, comparison, a, b);
+
+        let result = self.select(is_a, a, b);
+        let result = self.select(is_equal, equal_result, result);
+        let result = self.select(b_is_nan, a, result);
+        self.select(a_is_nan, b, result)
     }
 
     // Algorithm from: https://blog.regehr.org/archives/1063
-    fn rotate_right(&mut self, value: RValue<'gcc>, shift: RValue<'gcc>, width: u64) -> RValue<'gcc> {
-        let max = self.const_uint(shift.get_type(), width);
-        let shift = self.urem(shift, max);
-        let lhs = self.lshr(value, shift);
-        let result_neg = self.neg(shift);
-        let result_and =
-            self.and(
-                result_neg,
-                self.const_uint(shift.get_type(), width - 1),
-            );
-        let rhs = self.shl(value, result_and);
-        self.or(lhs, rhs)
+    // NOTE: GCC recognizes this pattern and emits a single rotate instruction (`rol`/`ror` on x86)
+    // as long as the shifts are done on an unsigned type and the shift amount is masked instead
+    // of computed with a remainder.
+    fn rotate(&mut self, value: RValue<'gcc>, shift: RValue<'gcc>, width: u64, is_left: bool) -> RValue<'gcc> {
+        let value_type = value.get_type();
+        let unsigned_type = value_type.to_unsigned(&self.cx);
+        let value = self.gcc_int_cast(value, unsigned_type);
+        let shift = self.gcc_int_cast(shift, unsigned_type);
+        let mask = self.gcc_int(unsigned_type, width as i64 - 1);
+        let shift = self.gcc_and(shift, mask);
+        let neg_shift = self.gcc_and(self.gcc_neg(shift), mask);
+        let (lhs, rhs) =
+            if is_left {
+                (self.gcc_shl(value, shift), self.gcc_lshr(value, neg_shift))
+            }
+            else {
+                (self.gcc_lshr(value, shift), self.gcc_shl(value, neg_shift))
+            };
+        let result = self.gcc_or(lhs, rhs);
+        self.gcc_int_cast(result, value_type)
     }
 
     fn saturating_add(&mut self, lhs: RValue<'gcc>, rhs: RValue<'gcc>, signed: bool, width: u64) -> RValue<'gcc> {
@@ -986,7 +1076,7 @@ fn saturating_add(&mut self, lhs: RValue<'gcc>, rhs: RValue<'gcc>, signed: bool,
         if signed {
             // Based on algorithm from: https://stackoverflow.com/a/56531252/389119
             let func = self.current_func.borrow().expect("func");
-            let res = func.new_local(None, result_type, "saturating_sum");
+            let res = func.new_local(self.location, result_type, "saturating_sum");
             let supports_native_type = self.is_native_int_type(result_type);
             let overflow =
                 if supports_native_type {
@@ -1000,7 +1090,7 @@ fn saturating_add(&mut self, lhs: RValue<'gcc>, rhs: RValue<'gcc>, signed: bool,
                             _ => unreachable!(),
                         };
                     let overflow_func = self.context.get_builtin_function(func_name);
-                    self.overflow_call(overflow_func, &[lhs, rhs, res.get_address(None)], None)
+                    self.overflow_call(overflow_func, &[lhs, rhs, res.get_address(self.location)], None)
                 }
                 else {
                     let func_name =
@@ -1008,38 +1098,20 @@ fn saturating_add(&mut self, lhs: RValue<'gcc>, rhs: RValue<'gcc>, signed: bool,
                             128 => "__rust_i128_addo",
                             _ => unreachable!(),
                         };
-                    let param_a = self.context.new_parameter(None, result_type, "a");
-                    let param_b = self.context.new_parameter(None, result_type, "b");
-                    let result_field = self.context.new_field(None, result_type, "result");
-                    let overflow_field = self.context.new_field(None, self.bool_type, "overflow");
-                    let return_type = self.context.new_struct_type(None, "result_overflow", &[result_field, overflow_field]);
-                    let func = self.context.new_function(None, FunctionType::Extern, return_type.as_type(), &[param_a, param_b], func_name, false);
-                    let result = self.context.new_call(None, func, &[lhs, rhs]);
-                    let overflow = result.access_field(None, overflow_field);
-                    let int_result = result.access_field(None, result_field);
-                    self.llbb().add_assignment(None, res, int_result);
+                    let param_a = self.context.new_parameter(self.location, result_type, "a");
+                    let param_b = self.context.new_parameter(self.location, result_type, "b");
+                    let result_field = self.context.new_field(self.location, result_type, "result");
+                    let overflow_field = self.context.new_field(self.location, self.bool_type, "overflow");
+                    let return_type = self.context.new_struct_type(self.location, "result_overflow", &[result_field, overflow_field]);
+                    let func = self.context.new_function(self.location, FunctionType::Extern, return_type.as_type(), &[param_a, param_b], func_name, false);
+                    let result = self.context.new_call(self.location, func, &[lhs, rhs]);
+                    let overflow = result.access_field(self.location, overflow_field);
+                    let int_result = result.access_field(self.location, result_field);
+                    self.llbb().add_assignment(self.location, res, int_result);
                     overflow
                 };
 
-            let then_block = func.new_block("then");
-            let after_block = func.new_block("after");
-
-            // Return `result_type`'s maximum or minimum value on overflow
-            // NOTE: convert the type to unsigned to have an unsigned shift.
-            let unsigned_type = result_type.to_unsigned(&self.cx);
-            let shifted = self.gcc_lshr(self.gcc_int_cast(lhs, unsigned_type), self.gcc_int(unsigned_type, width as i64 - 1));
-            let uint_max = self.gcc_not(self.gcc_int(unsigned_type, 0));
-            let int_max = self.gcc_lshr(uint_max, self.gcc_int(unsigned_type, 1));
-            then_block.add_assignment(None, res, self.gcc_int_cast(self.gcc_add(shifted, int_max), result_type));
-            then_block.end_with_jump(None, after_block);
-
-            self.llbb().end_with_conditional(None, overflow, then_block, after_block);
-
-            // NOTE: since jumps were added in a place rustc does not
-            // expect, the current block in the state need to be updated.
-            self.switch_to_block(after_block);
-
-            res.to_rvalue()
+            self.saturate_on_overflow(res.to_rvalue(), overflow, lhs, width)
         }
         else {
             // Algorithm from: http://locklessinc.com/articles/sat_arithmetic/
@@ -1050,13 +1122,31 @@ fn saturating_add(&mut self, lhs: RValue<'gcc>, rhs: RValue<'gcc>, signed: bool,
         }
     }
 
+    /// Select `result_type`'s maximum or minimum value, depending on the sign of `lhs`, when
+    /// `overflow` is set. This is done without branches so that GCC can emit a conditional move.
+    fn saturate_on_overflow(&mut self, res: RValue<'gcc>, overflow: RValue<'gcc>, lhs: RValue<'gcc>, width: u64) -> RValue<'gcc> {
+        let result_type = res.get_type();
+        // NOTE: convert the type to unsigned to have an unsigned shift.
+        let unsigned_type = result_type.to_unsigned(&self.cx);
+        let shifted = self.gcc_lshr(self.gcc_int_cast(lhs, unsigned_type), self.gcc_int(unsigned_type, width as i64 - 1));
+        let uint_max = self.gcc_not(self.gcc_int(unsigned_type, 0));
+        let int_max = self.gcc_lshr(uint_max, self.gcc_int(unsigned_type, 1));
+        let saturated = self.gcc_int_cast(self.gcc_add(shifted, int_max), result_type);
+
+        // mask is all ones on overflow and zero otherwise.
+        let mask = self.gcc_neg(self.gcc_int_cast(overflow, result_type));
+        let saturated = self.gcc_and(saturated, mask);
+        let res = self.gcc_and(res, self.gcc_not(mask));
+        self.gcc_or(res, saturated)
+    }
+
     // Algorithm from: https://locklessinc.com/articles/sat_arithmetic/
     fn saturating_sub(&mut self, lhs: RValue<'gcc>, rhs: RValue<'gcc>, signed: bool, width: u64) -> RValue<'gcc> {
         let result_type = lhs.get_type();
         if signed {
             // Based on algorithm from: https://stackoverflow.com/a/56531252/389119
             let func = self.current_func.borrow().expect("func");
-            let res = func.new_local(None, result_type, "saturating_diff");
+            let res = func.new_local(self.location, result_type, "saturating_diff");
             let supports_native_type = self.is_native_int_type(result_type);
             let overflow =
                 if supports_native_type {
@@ -1070,7 +1160,7 @@ fn saturating_sub(&mut self, lhs: RValue<'gcc>, rhs: RValue<'gcc>, signed: bool,
                             _ => unreachable!(),
                         };
                     let overflow_func = self.context.get_builtin_function(func_name);
-                    self.overflow_call(overflow_func, &[lhs, rhs, res.get_address(None)], None)
+                    self.overflow_call(overflow_func, &[lhs, rhs, res.get_address(self.location)], None)
                 }
                 else {
                     let func_name =
@@ -1078,38 +1168,20 @@ fn saturating_sub(&mut self, lhs: RValue<'gcc>, rhs: RValue<'gcc>, signed: bool,
                             128 => "__rust_i128_subo",
                             _ => unreachable!(),
                         };
-                    let param_a = self.context.new_parameter(None, result_type, "a");
-                    let param_b = self.context.new_parameter(None, result_type, "b");
-                    let result_field = self.context.new_field(None, result_type, "result");
-                    let overflow_field = self.context.new_field(None, self.bool_type, "overflow");
-                    let return_type = self.context.new_struct_type(None, "result_overflow", &[result_field, overflow_field]);
-                    let func = self.context.new_function(None, FunctionType::Extern, return_type.as_type(), &[param_a, param_b], func_name, false);
-                    let result = self.context.new_call(None, func, &[lhs, rhs]);
-                    let overflow = result.access_field(None, overflow_field);
-                    let int_result = result.access_field(None, result_field);
-                    self.llbb().add_assignment(None, res, int_result);
+                    let param_a = self.context.new_parameter(self.location, result_type, "a");
+                    let param_b = self.context.new_parameter(self.location, result_type, "b");
+                    let result_field = self.context.new_field(self.location, result_type, "result");
+                    let overflow_field = self.context.new_field(self.location, self.bool_type, "overflow");
+                    let return_type = self.context.new_struct_type(self.location, "result_overflow", &[result_field, overflow_field]);
+                    let func = self.context.new_function(self.location, FunctionType::Extern, return_type.as_type(), &[param_a, param_b], func_name, false);
+                    let result = self.context.new_call(self.location, func, &[lhs, rhs]);
+                    let overflow = result.access_field(self.location, overflow_field);
+                    let int_result = result.access_field(self.location, result_field);
+                    self.llbb().add_assignment(self.location, res, int_result);
                     overflow
                 };
 
-            let then_block = func.new_block("then");
-            let after_block = func.new_block("after");
-
-            // Return `result_type`'s maximum or minimum value on overflow
-            // NOTE: convert the type to unsigned to have an unsigned shift.
-            let unsigned_type = result_type.to_unsigned(&self.cx);
-            let shifted = self.gcc_lshr(self.gcc_int_cast(lhs, unsigned_type), self.gcc_int(unsigned_type, width as i64 - 1));
-            let uint_max = self.gcc_not(self.gcc_int(unsigned_type, 0));
-            let int_max = self.gcc_lshr(uint_max, self.gcc_int(unsigned_type, 1));
-            then_block.add_assignment(None, res, self.gcc_int_cast(self.gcc_add(shifted, int_max), result_type));
-            then_block.end_with_jump(None, after_block);
-
-            self.llbb().end_with_conditional(None, overflow, then_block, after_block);
-
-            // NOTE: since jumps were added in a place rustc does not
-            // expect, the current block in the state need to be updated.
-            self.switch_to_block(after_block);
-
-            res.to_rvalue()
+            self.saturate_on_overflow(res.to_rvalue(), overflow, lhs, width)
         }
         else {
             let res = self.gcc_sub(lhs, rhs);
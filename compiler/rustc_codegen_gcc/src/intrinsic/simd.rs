@@ -32,9 +32,9 @@
     InvalidMonomorphizationReturnIntegerType,
     InvalidMonomorphizationMismatchedLengths,
     InvalidMonomorphizationUnsupportedCast,
-    InvalidMonomorphizationUnsupportedOperation
+    InvalidMonomorphizationUnsupportedOperation,
+    InvalidMonomorphizationExpectedPointerVector,
 };
-use crate::intrinsic;
 
 pub fn generic_simd_intrinsic<'a, 'gcc, 'tcx>(bx: &mut Builder<'a, 'gcc, 'tcx>, name: Symbol, callee_ty: Ty<'tcx>, args: &[OperandRef<'tcx, RValue<'gcc>>], ret_ty: Ty<'tcx>, llret_ty: Type<'gcc>, span: Span) -> Result<RValue<'gcc>, ()> {
     // macros for error handling:
@@ -252,6 +252,154 @@ macro_rules! require_simd {
         return Ok(bx.context.new_vector_access(None, vector, args[1].immediate()).to_rvalue());
     }
 
+    #[cfg(feature="master")]
+    if name == sym::simd_swizzle_dyn {
+        require!(
+            matches!(in_elem.kind(), ty::Uint(ty::UintTy::U8)),
+            InvalidMonomorphizationUnsupportedOperation { span, name, in_ty, in_elem }
+        );
+        let values = args[0].immediate();
+        let indices = args[1].immediate();
+        let vector_type = values.get_type();
+        let element_type = vector_type.unqualified().dyncast_vector().expect("vector type").get_element_type();
+
+        // NOTE: pshufb is an SSSE3 instruction, which is not enabled on the baseline x86-64 target.
+        if in_len == 16 && bx.sess().target.arch == "x86_64" && bx.sess().target_features.contains(&Symbol::intern("ssse3")) {
+            // NOTE: pshufb only uses the 4 low bits of the index and zeroes the lane when the high
+            // bit of the index is set, so do a saturating add of 0x70 to set this bit for
+            // out-of-range indices while keeping the low bits of the in-range ones.
+            let offset = bx.context.new_rvalue_from_int(element_type, 0x70);
+            let offset = bx.context.new_rvalue_from_vector(None, vector_type, &[offset; 16]);
+            let saturating_add = bx.context.get_target_builtin_function("__builtin_ia32_paddusb128");
+            let param_type = saturating_add.get_param(0).to_rvalue().get_type();
+            let indices = bx.context.new_call(None, saturating_add, &[
+                bx.cx.bitcast_if_needed(indices, param_type),
+                bx.cx.bitcast_if_needed(offset, param_type),
+            ]);
+            let shuffle = bx.context.get_target_builtin_function("__builtin_ia32_pshufb128");
+            let param_type = shuffle.get_param(0).to_rvalue().get_type();
+            let result = bx.context.new_call(None, shuffle, &[
+                bx.cx.bitcast_if_needed(values, param_type),
+                bx.cx.bitcast_if_needed(indices, param_type),
+            ]);
+            return Ok(bx.cx.bitcast_if_needed(result, vector_type));
+        }
+
+        if in_len == 16 && bx.sess().target.arch == "aarch64" && bx.sess().target_features.contains(&sym::neon) {
+            // NOTE: tbl already zeroes the lanes with an out-of-range index.
+            let table_lookup = bx.context.get_target_builtin_function("__builtin_aarch64_qtbl1v16qi");
+            let param_type = table_lookup.get_param(0).to_rvalue().get_type();
+            let result = bx.context.new_call(None, table_lookup, &[
+                bx.cx.bitcast_if_needed(values, param_type),
+                bx.cx.bitcast_if_needed(indices, param_type),
+            ]);
+            return Ok(bx.cx.bitcast_if_needed(result, vector_type));
+        }
+
+        // Scalar fallback: out-of-range indices select 0.
+        let len = bx.context.new_rvalue_from_int(element_type, in_len as i32);
+        let mut elements = vec![];
+        for i in 0..in_len {
+            let i = bx.context.new_rvalue_from_int(bx.int_type, i as i32);
+            let index = bx.context.new_vector_access(None, indices, i).to_rvalue();
+            let in_range = bx.context.new_comparison(None, gccjit::ComparisonOp::LessThan, index, len);
+            let in_range = bx.context.new_cast(None, in_range, element_type);
+            // NOTE: the modulo keeps the access in bounds and the multiplication by in_range
+            // zeroes the lanes with an out-of-range index.
+            let index = bx.context.new_binary_op(None, BinaryOp::Modulo, element_type, index, len);
+            let index = bx.context.new_cast(None, index, bx.int_type);
+            let value = bx.context.new_vector_access(None, values, index).to_rvalue();
+            elements.push(bx.context.new_binary_op(None, BinaryOp::Mult, element_type, value, in_range));
+        }
+        return Ok(bx.context.new_rvalue_from_vector(None, vector_type, &elements));
+    }
+
+    #[cfg(feature="master")]
+    if name == sym::simd_gather || name == sym::simd_scatter {
+        // simd_gather(values: <N x T>, pointers: <N x *const T>, mask: <N x i{M}>) -> <N x T>
+        // simd_scatter(values: <N x T>, pointers: <N x *mut T>, mask: <N x i{M}>)
+        let is_gather = name == sym::simd_gather;
+        require_simd!(arg_tys[1], "second");
+        require_simd!(arg_tys[2], "third");
+        let (pointer_len, pointer_elem) = arg_tys[1].simd_size_and_type(bx.tcx());
+        let (mask_len, mask_elem) = arg_tys[2].simd_size_and_type(bx.tcx());
+        require!(
+            pointer_len == in_len,
+            InvalidMonomorphizationMismatchedLengths { span, name, m_len: pointer_len, v_len: in_len }
+        );
+        require!(
+            mask_len == in_len,
+            InvalidMonomorphizationMismatchedLengths { span, name, m_len: mask_len, v_len: in_len }
+        );
+        let mutability = if is_gather { "const" } else { "mut" };
+        match pointer_elem.kind() {
+            ty::RawPtr(pointer) if pointer.ty == in_elem && (is_gather || pointer.mutbl == hir::Mutability::Mut) => {}
+            _ => return_error!(
+                InvalidMonomorphizationExpectedPointerVector { span, name, vec_ty: arg_tys[1], in_elem, mutability }
+            ),
+        }
+        match mask_elem.kind() {
+            ty::Int(_) => {}
+            _ => return_error!(InvalidMonomorphizationMaskType { span, name, ty: mask_elem }),
+        }
+        if is_gather {
+            require_simd!(ret_ty, "return");
+            let (_, out_ty) = ret_ty.simd_size_and_type(bx.tcx());
+            require!(
+                ret_ty == in_ty,
+                InvalidMonomorphizationReturnElement { span, name, in_elem, in_ty, ret_ty, out_ty }
+            );
+        }
+
+        // TODO: use the gather builtins when the target supports them.
+        let values = args[0].immediate();
+        let pointers = args[1].immediate();
+        let mask = args[2].immediate();
+        let vector_type = values.get_type();
+        let element_type = vector_type.unqualified().dyncast_vector().expect("vector type").get_element_type();
+        let mask_element_type = mask.get_type().unqualified().dyncast_vector().expect("vector type").get_element_type();
+        let usize_type = bx.usize_type;
+
+        // NOTE: a masked-off lane accesses a local slot instead of its pointer, so that every lane
+        // can be loaded or stored without a branch. For a gather, this slot contains the value of
+        // the lane, which is then the result for this lane.
+        let slots_type = bx.context.new_array_type(None, element_type, in_len as i32);
+        let slots = bx.current_func().new_local(None, slots_type, "masked_off_lanes");
+        let zero = bx.context.new_rvalue_zero(mask_element_type);
+        let mut elements = vec![];
+        for i in 0..in_len {
+            let index = bx.context.new_rvalue_from_int(bx.int_type, i as i32);
+            let value = bx.context.new_vector_access(None, values, index).to_rvalue();
+            let pointer = bx.context.new_vector_access(None, pointers, index).to_rvalue();
+            let enabled = bx.context.new_vector_access(None, mask, index).to_rvalue();
+            let slot = bx.context.new_array_access(None, slots, index);
+            if is_gather {
+                bx.llbb().add_assignment(None, slot, value);
+            }
+
+            let enabled = bx.context.new_comparison(None, gccjit::ComparisonOp::NotEquals, enabled, zero);
+            let enabled = bx.context.new_cast(None, enabled, usize_type);
+            let select_pointer = bx.context.new_unary_op(None, gccjit::UnaryOp::Minus, usize_type, enabled);
+            let select_slot = bx.context.new_unary_op(None, gccjit::UnaryOp::BitwiseNegate, usize_type, select_pointer);
+            let pointer = bx.context.new_cast(None, pointer, usize_type);
+            let slot_address = bx.context.new_cast(None, slot.get_address(None), usize_type);
+            let address = (pointer & select_pointer) | (slot_address & select_slot);
+            let address = bx.context.new_cast(None, address, element_type.make_pointer());
+
+            if is_gather {
+                elements.push(address.dereference(None).to_rvalue());
+            }
+            else {
+                bx.llbb().add_assignment(None, address.dereference(None), value);
+            }
+        }
+
+        if is_gather {
+            return Ok(bx.context.new_rvalue_from_vector(None, vector_type, &elements));
+        }
+        return Ok(bx.context.new_rvalue_zero(bx.int_type));
+    }
+
     if name == sym::simd_select {
         let m_elem_ty = in_elem;
         let m_len = in_len;
@@ -436,33 +584,69 @@ macro_rules! return_error {
                 return_error!(InvalidMonomorphizationNotFloat { span, name, ty: in_ty });
             };
 
-        let vec_ty = bx.cx.type_vector(elem_ty, in_len);
-
-        let (intr_name, fn_ty) =
+        let intr_name =
             match name {
-                sym::simd_ceil => ("ceil", bx.type_func(&[vec_ty], vec_ty)),
-                sym::simd_fabs => ("fabs", bx.type_func(&[vec_ty], vec_ty)), // TODO(antoyo): pand with 170141183420855150465331762880109871103
-                sym::simd_fcos => ("cos", bx.type_func(&[vec_ty], vec_ty)),
-                sym::simd_fexp2 => ("exp2", bx.type_func(&[vec_ty], vec_ty)),
-                sym::simd_fexp => ("exp", bx.type_func(&[vec_ty], vec_ty)),
-                sym::simd_flog10 => ("log10", bx.type_func(&[vec_ty], vec_ty)),
-                sym::simd_flog2 => ("log2", bx.type_func(&[vec_ty], vec_ty)),
-                sym::simd_flog => ("log", bx.type_func(&[vec_ty], vec_ty)),
-                sym::simd_floor => ("floor", bx.type_func(&[vec_ty], vec_ty)),
-                sym::simd_fma => ("fma", bx.type_func(&[vec_ty, vec_ty, vec_ty], vec_ty)),
-                sym::simd_fpowi => ("powi", bx.type_func(&[vec_ty, bx.type_i32()], vec_ty)),
-                sym::simd_fpow => ("pow", bx.type_func(&[vec_ty, vec_ty], vec_ty)),
-                sym::simd_fsin => ("sin", bx.type_func(&[vec_ty], vec_ty)),
-                sym::simd_fsqrt => ("sqrt", bx.type_func(&[vec_ty], vec_ty)),
-                sym::simd_round => ("round", bx.type_func(&[vec_ty], vec_ty)),
-                sym::simd_trunc => ("trunc", bx.type_func(&[vec_ty], vec_ty)),
+                sym::simd_ceil => "ceil",
+                sym::simd_fabs => "fabs", // TODO(antoyo): pand with 170141183420855150465331762880109871103
+                sym::simd_fcos => "cos",
+                sym::simd_fexp2 => "exp2",
+                sym::simd_fexp => "exp",
+                sym::simd_flog10 => "log10",
+                sym::simd_flog2 => "log2",
+                sym::simd_flog => "log",
+                sym::simd_floor => "floor",
+                sym::simd_fma => "fma",
+                sym::simd_fmax => "fmax",
+                sym::simd_fmin => "fmin",
+                sym::simd_fpowi => "__builtin_powi",
+                sym::simd_fpow => "pow",
+                sym::simd_fsin => "sin",
+                sym::simd_fsqrt => "sqrt",
+                sym::simd_round => "round",
+                sym::simd_trunc => "trunc",
                 _ => return_error!(InvalidMonomorphizationUnrecognized { span, name })
             };
-        let llvm_name = &format!("llvm.{0}.v{1}{2}", intr_name, in_len, elem_ty_str);
-        let function = intrinsic::llvm::intrinsic(llvm_name, &bx.cx);
-        let function: RValue<'gcc> = unsafe { std::mem::transmute(function) };
-        let c = bx.call(fn_ty, function, &args.iter().map(|arg| arg.immediate()).collect::<Vec<_>>(), None);
-        Ok(c)
+        // NOTE: the C math functions have the NaN semantics expected by Rust, e.g. fmin and fmax
+        // return the non-NaN operand.
+        let suffix =
+            if elem_ty_str == "f32" {
+                "f"
+            }
+            else {
+                ""
+            };
+        let function = bx.context.get_builtin_function(&format!("{}{}", intr_name, suffix));
+
+        // NOTE: call the scalar function on each lane and let GCC vectorize it when the target
+        // supports it.
+        let vector_type = args[0].immediate().get_type();
+        let array_type = bx.context.new_array_type(None, elem_ty, in_len as i32);
+        // TODO: switch to using new_vector_access.
+        let arrays: Vec<_> = args.iter()
+            .map(|arg| {
+                let value = arg.immediate();
+                if value.get_type().is_vector() {
+                    Some(bx.context.new_bitcast(None, value, array_type))
+                }
+                else {
+                    // The exponent of powi is a scalar.
+                    None
+                }
+            })
+            .collect();
+        let elements: Vec<_> = (0..in_len)
+            .map(|i| {
+                let index = bx.context.new_rvalue_from_int(bx.int_type, i as i32);
+                let arguments: Vec<_> = args.iter().zip(&arrays)
+                    .map(|(arg, array)| match array {
+                        Some(array) => bx.context.new_array_access(None, *array, index).to_rvalue(),
+                        None => arg.immediate(),
+                    })
+                    .collect();
+                bx.context.new_call(None, function, &arguments)
+            })
+            .collect();
+        Ok(bx.context.new_rvalue_from_vector(None, vector_type, &elements))
     }
 
     if std::matches!(
@@ -477,6 +661,8 @@ macro_rules! return_error {
             | sym::simd_flog
             | sym::simd_floor
             | sym::simd_fma
+            | sym::simd_fmax
+            | sym::simd_fmin
             | sym::simd_fpow
             | sym::simd_fpowi
             | sym::simd_fsin
@@ -496,7 +682,7 @@ macro_rules! return_error {
         simd_shl: Uint, Int => shl;
         simd_shr: Uint => lshr, Int => ashr;
         simd_and: Uint, Int => and;
-        simd_or: Uint, Int => or; // FIXME(antoyo): calling `or` might not work on vectors.
+        simd_or: Uint, Int => or;
         simd_xor: Uint, Int => xor;
     }
 
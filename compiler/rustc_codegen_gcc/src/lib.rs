@@ -27,6 +27,7 @@
 extern crate rustc_data_structures;
 extern crate rustc_errors;
 extern crate rustc_hir;
+extern crate rustc_index;
 extern crate rustc_macros;
 extern crate rustc_metadata;
 extern crate rustc_middle;
@@ -63,7 +64,7 @@
 use std::any::Any;
 use std::sync::{Arc, Mutex};
 
-use crate::errors::LTONotSupported;
+use crate::errors::{LTONotSupported, SplitDebuginfoNotSupported};
 use gccjit::{Context, OptimizationLevel, CType};
 use rustc_ast::expand::allocator::AllocatorKind;
 use rustc_codegen_ssa::{CodegenResults, CompiledModule, ModuleCodegen};
@@ -78,10 +79,11 @@
 use rustc_middle::dep_graph::{WorkProduct, WorkProductId};
 use rustc_middle::ty::TyCtxt;
 use rustc_middle::ty::query::Providers;
-use rustc_session::config::{Lto, OptLevel, OutputFilenames};
+use rustc_session::config::{DebugInfo, Lto, OptLevel, OutputFilenames};
 use rustc_session::Session;
 use rustc_span::Symbol;
 use rustc_span::fatal_error::FatalError;
+use rustc_target::spec::SplitDebuginfo;
 use tempfile::TempDir;
 
 pub struct PrintOnPanic<F: Fn() -> String>(pub F);
@@ -105,6 +107,17 @@ fn init(&self, sess: &Session) {
             sess.emit_warning(LTONotSupported {});
         }
 
+        if sess.opts.debuginfo != DebugInfo::None && sess.target_can_use_split_dwarf() && sess.split_debuginfo() != SplitDebuginfo::Off {
+            sess.emit_warning(SplitDebuginfoNotSupported);
+        }
+
+        // NOTE: with -Zlink-only, the objects were already compiled by a previous invocation of
+        // rustc (-Zno-link) and we only need to run the linker, so there's no need to check what
+        // libgccjit supports.
+        if sess.opts.unstable_opts.link_only {
+            return;
+        }
+
         let temp_dir = TempDir::new().expect("cannot create temporary directory");
         let temp_file = temp_dir.into_path().join("result.asm");
         let check_context = Context::default();
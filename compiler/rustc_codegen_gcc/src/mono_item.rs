@@ -28,7 +28,8 @@ fn predefine_fn(&self, instance: Instance<'tcx>, linkage: Linkage, _visibility:
 
         let fn_abi = self.fn_abi_of_instance(instance, ty::List::empty());
         self.linkage.set(base::linkage_to_gcc(linkage));
-        let _decl = self.declare_fn(symbol_name, &fn_abi);
+        let decl = self.declare_fn(symbol_name, &fn_abi);
+        self.check_calling_convention(self.rvalue_as_function(decl));
         //let attrs = self.tcx.codegen_fn_attrs(instance.def_id());
 
         // TODO(antoyo): call set_link_section() to allow initializing argc/argv.
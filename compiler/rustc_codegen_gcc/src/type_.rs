@@ -5,7 +5,7 @@
 use rustc_codegen_ssa::common::TypeKind;
 use rustc_middle::{bug, ty};
 use rustc_middle::ty::layout::TyAndLayout;
-use rustc_target::abi::{AddressSpace, Align, Integer, Size};
+use rustc_target::abi::{AddressSpace, Align, Integer, Size, Variants};
 
 use crate::common::TypeReflection;
 use crate::context::CodegenCx;
@@ -213,8 +213,16 @@ pub fn type_padding_filler(&self, size: Size, align: Align) -> Type<'gcc> {
     }
 
     pub fn set_struct_body(&self, typ: Struct<'gcc>, fields: &[Type<'gcc>], packed: bool) {
-        let fields: Vec<_> = fields.iter().enumerate()
-            .map(|(index, field)| self.context.new_field(None, *field, &format!("field_{}", index)))
+        let field_names: Vec<_> = (0..fields.len())
+            .map(|index| format!("field_{}", index))
+            .collect();
+        self.set_struct_body_with_names(typ, fields, &field_names, packed);
+    }
+
+    pub fn set_struct_body_with_names(&self, typ: Struct<'gcc>, fields: &[Type<'gcc>], field_names: &[String], packed: bool) {
+        assert_eq!(fields.len(), field_names.len());
+        let fields: Vec<_> = fields.iter().zip(field_names)
+            .map(|(field, name)| self.context.new_field(None, *field, name))
             .collect();
         typ.set_fields(None, &fields);
         if packed {
@@ -253,13 +261,31 @@ pub fn type_bool(&self) -> Type<'gcc> {
     }
 }
 
-pub fn struct_fields<'gcc, 'tcx>(cx: &CodegenCx<'gcc, 'tcx>, layout: TyAndLayout<'tcx>) -> (Vec<Type<'gcc>>, bool) {
+/// Returns the name of the field `index` of `layout` as it appears in the debuginfo: the fields of
+/// tuples, closures and tuple structs are named `__0`, `__1` and so on, like in rustc_codegen_llvm.
+fn field_name<'tcx>(layout: TyAndLayout<'tcx>, index: usize) -> String {
+    let name =
+        match (layout.ty.kind(), &layout.variants) {
+            (ty::Adt(def, _), &Variants::Single { index: variant_index }) if !def.is_union() =>
+                def.variant(variant_index).fields.get(index).map(|field| field.name),
+            _ => None,
+        };
+    match name {
+        Some(name) if !name.as_str().starts_with(|c: char| c.is_ascii_digit()) => name.to_string(),
+        _ => format!("__{}", index),
+    }
+}
+
+/// Returns the types of the fields of `layout` interleaved with the padding, their names and
+/// whether the struct needs to be packed.
+pub fn struct_fields<'gcc, 'tcx>(cx: &CodegenCx<'gcc, 'tcx>, layout: TyAndLayout<'tcx>) -> (Vec<Type<'gcc>>, Vec<String>, bool) {
     let field_count = layout.fields.count();
 
     let mut packed = false;
     let mut offset = Size::ZERO;
     let mut prev_effective_align = layout.align.abi;
     let mut result: Vec<_> = Vec::with_capacity(1 + field_count * 2);
+    let mut names: Vec<_> = Vec::with_capacity(1 + field_count * 2);
     for i in layout.fields.index_by_increasing_offset() {
         let target_offset = layout.fields.offset(i as usize);
         let field = layout.field(cx, i);
@@ -272,8 +298,11 @@ pub fn struct_fields<'gcc, 'tcx>(cx: &CodegenCx<'gcc, 'tcx>, layout: TyAndLayout
         let padding_align = prev_effective_align.min(effective_field_align);
         assert_eq!(offset.align_to(padding_align) + padding, target_offset);
         result.push(cx.type_padding_filler(padding, padding_align));
+        // NOTE: the padding names contain a `.` so that they cannot collide with a Rust field.
+        names.push(format!("padding.{}", i));
 
         result.push(field.gcc_type(cx, !field.ty.is_any_ptr())); // FIXME(antoyo): might need to check if the type is inside another, like Box<Type>.
+        names.push(field_name(layout, i));
         offset = target_offset + field.size;
         prev_effective_align = effective_field_align;
     }
@@ -285,10 +314,11 @@ pub fn struct_fields<'gcc, 'tcx>(cx: &CodegenCx<'gcc, 'tcx>, layout: TyAndLayout
         let padding_align = prev_effective_align;
         assert_eq!(offset.align_to(padding_align) + padding, layout.size);
         result.push(cx.type_padding_filler(padding, padding_align));
+        names.push("padding.end".to_string());
         assert_eq!(result.len(), 1 + field_count * 2);
     }
 
-    (result, packed)
+    (result, names, packed)
 }
 
 impl<'gcc, 'tcx> TypeMembershipMethods<'tcx> for CodegenCx<'gcc, 'tcx> {
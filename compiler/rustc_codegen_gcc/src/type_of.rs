@@ -118,7 +118,7 @@ pub fn uncached_gcc_type<'gcc, 'tcx>(cx: &CodegenCx<'gcc, 'tcx>, layout: TyAndLa
         FieldsShape::Arbitrary { .. } =>
             match name {
                 None => {
-                    let (gcc_fields, packed) = struct_fields(cx, layout);
+                    let (gcc_fields, _, packed) = struct_fields(cx, layout);
                     cx.type_struct(&gcc_fields, packed)
                 },
                 Some(ref name) => {
@@ -203,8 +203,8 @@ fn gcc_type<'gcc>(&self, cx: &CodegenCx<'gcc, 'tcx>, set_fields: bool) -> Type<'
             if let Some((struct_type, layout)) = type_to_set_fields {
                 // Since we might be trying to generate a type containing another type which is not
                 // completely generated yet, we deferred setting the fields until now.
-                let (fields, packed) = struct_fields(cx, layout);
-                cx.set_struct_body(struct_type, &fields, packed);
+                let (fields, field_names, packed) = struct_fields(cx, layout);
+                cx.set_struct_body_with_names(struct_type, &fields, &field_names, packed);
             }
             return ty;
         }
@@ -231,8 +231,8 @@ fn gcc_type<'gcc>(&self, cx: &CodegenCx<'gcc, 'tcx>, set_fields: bool) -> Type<'
         cx.types.borrow_mut().insert((self.ty, variant_index), ty);
 
         if let Some((ty, layout)) = defer {
-            let (fields, packed) = struct_fields(cx, layout);
-            cx.set_struct_body(ty, &fields, packed);
+            let (fields, field_names, packed) = struct_fields(cx, layout);
+            cx.set_struct_body_with_names(ty, &fields, &field_names, packed);
         }
 
         ty
@@ -54,6 +54,11 @@ while [[ $# -gt 0 ]]; do
             shift
             ;;
 
+        "--test-portable-simd")
+            func=test_portable_simd
+            shift
+            ;;
+
         "--clean-ui-tests")
             func=clean_ui_tests
             shift
@@ -64,6 +69,11 @@ while [[ $# -gt 0 ]]; do
             shift
             ;;
 
+        "--asm-tests")
+            func=asm_tests
+            shift
+            ;;
+
         "--extended-tests")
             func=extended_sysroot_tests
             shift
@@ -115,6 +125,44 @@ function mini_tests() {
     echo "[AOT] mini_core_hello_world"
     $RUSTC example/mini_core_hello_world.rs --crate-name mini_core_hello_world --crate-type bin -g --target $TARGET_TRIPLE
     $RUN_WRAPPER ./target/out/mini_core_hello_world abc bcd
+
+    echo "[AOT] mini_core_hello_world (separate link)"
+    rm -f ./target/out/mini_core_hello_world ./target/out/mini_core_hello_world.rlink
+    $RUSTC example/mini_core_hello_world.rs --crate-name mini_core_hello_world --crate-type bin -g --target $TARGET_TRIPLE -Zno-link
+    $RUSTC ./target/out/mini_core_hello_world.rlink --crate-name mini_core_hello_world --crate-type bin --target $TARGET_TRIPLE -Zlink-only
+    $RUN_WRAPPER ./target/out/mini_core_hello_world abc bcd
+
+    if [[ "$TARGET_TRIPLE" == x86_64* ]]; then
+        # The -asan-* LLVM arguments are only translated to GCC options when ASan is enabled: an
+        # outlined check must then be emitted for every access.
+        echo "[BUILD] kasan"
+        $RUSTC example/kasan.rs --crate-type lib --emit obj --target x86_64-unknown-none -Zsanitizer=kernel-address \
+            -Cllvm-args=-asan-instrumentation-with-call-threshold=0 -Cllvm-args=-asan-mapping-offset=0xdffffc0000000000
+        nm target/out/kasan.o | grep -q __asan_load4_noabort
+        nm target/out/kasan.o | grep -q __asan_store4_noabort
+    fi
+}
+
+# Each file of tests/assembly is compiled to assembly when its `// compile-flags:` target is the one
+# libgccjit was built for, and every `// CHECK:` extended regex must match a line of the output.
+function asm_tests() {
+    for test in tests/assembly/*.rs; do
+        flags=$(sed -n 's|^// compile-flags: ||p' $test)
+        target=$(echo $flags | sed 's|.*--target \([^ ]*\).*|\1|')
+        if [[ "$target" != "$TARGET_TRIPLE" ]]; then
+            continue
+        fi
+
+        echo "[ASM] $test"
+        name=$(basename $test .rs)
+        $RUSTC $test --crate-name asm_$name --crate-type lib --emit asm $flags
+        sed -n 's|^// CHECK: ||p' $test | while read -r pattern; do
+            if ! grep -Eq -- "$pattern" target/out/asm_$name.s; then
+                echo "$test: no line matches \`$pattern\`"
+                exit 1
+            fi
+        done
+    done
 }
 
 function build_sysroot() {
@@ -171,6 +219,14 @@ function test_libcore() {
     popd
 }
 
+function test_portable_simd() {
+    pushd build_sysroot/sysroot_src/library/portable-simd
+    echo "[TEST] portable-simd"
+    rm -r ./target || true
+    ../../../../cargo.sh test
+    popd
+}
+
 #echo
 #echo "[BENCH COMPILE] mod_bench"
 
@@ -281,6 +337,7 @@ function clean_ui_tests() {
 function all() {
     clean
     mini_tests
+    asm_tests
     build_sysroot
     std_tests
     test_libcore
@@ -0,0 +1,93 @@
+// compile-flags: --target aarch64-unknown-linux-gnu -C opt-level=2 -C target-feature=+sve
+
+#![feature(no_core, lang_items, link_llvm_intrinsics, rustc_attrs, repr_simd)]
+#![no_core]
+#![allow(non_camel_case_types)]
+
+#[rustc_builtin_macro]
+macro_rules! asm {
+    () => {};
+}
+
+#[lang = "sized"]
+trait Sized {}
+#[lang = "copy"]
+trait Copy {}
+
+impl Copy for i8 {}
+impl Copy for i32 {}
+impl Copy for i64 {}
+impl Copy for f32 {}
+impl Copy for f64 {}
+
+#[repr(simd)]
+pub struct i8x16(i8, i8, i8, i8, i8, i8, i8, i8, i8, i8, i8, i8, i8, i8, i8, i8);
+impl Copy for i8x16 {}
+
+// CHECK: rbit x[0-9]+, x[0-9]+
+#[no_mangle]
+pub unsafe fn reg_i64(x: i64) -> i64 {
+    let y;
+    asm!("rbit {}, {}", lateout(reg) y, in(reg) x);
+    y
+}
+
+// CHECK: rbit w[0-9]+, w[0-9]+
+#[no_mangle]
+pub unsafe fn reg_i32(x: i32) -> i32 {
+    let y;
+    asm!("rbit {:w}, {:w}", lateout(reg) y, in(reg) x);
+    y
+}
+
+// CHECK: fsqrt d[0-9]+, d[0-9]+
+#[no_mangle]
+pub unsafe fn vreg_f64(x: f64) -> f64 {
+    let y;
+    asm!("fsqrt {:d}, {:d}", lateout(vreg) y, in(vreg) x);
+    y
+}
+
+// CHECK: not v[0-9]+\.16b, v[0-9]+\.16b
+#[no_mangle]
+pub unsafe fn vreg_i8x16(x: i8x16) -> i8x16 {
+    let y;
+    asm!("not {}.16b, {}.16b", lateout(vreg) y, in(vreg) x);
+    y
+}
+
+// CHECK: frintx s([0-9]|1[0-5]), s([0-9]|1[0-5])$
+#[no_mangle]
+pub unsafe fn vreg_low16_f32(x: f32) -> f32 {
+    let y;
+    asm!("frintx {:s}, {:s}", lateout(vreg_low16) y, in(vreg_low16) x);
+    y
+}
+
+// NOTE: x is passed in x1, so it must be moved to the explicit register.
+// CHECK: mov[[:space:]]+x0, x1$
+// CHECK: add x0, x0, #1
+#[no_mangle]
+pub unsafe fn x0_i64(_unused: i64, x: i64) -> i64 {
+    let mut y = x;
+    asm!("add x0, x0, #1", inout("x0") y);
+    y
+}
+
+// CHECK: ptrue p0\.b
+#[no_mangle]
+pub unsafe fn p0_clobber() {
+    asm!("ptrue p0.b", out("p0") _);
+}
+
+extern "C" {
+    #[link_name = "llvm.aarch64.isb"]
+    fn isb(option: i32);
+}
+
+// NOTE: this is the intrinsic called by `core::hint::spin_loop`.
+// CHECK: isb$
+#[no_mangle]
+pub unsafe fn spin_loop() {
+    isb(15);
+}
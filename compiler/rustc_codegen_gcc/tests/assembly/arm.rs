@@ -0,0 +1,103 @@
+// compile-flags: --target armv7-unknown-linux-gnueabihf -C target-feature=+neon
+
+#![feature(no_core, lang_items, rustc_attrs, repr_simd)]
+#![no_core]
+#![allow(non_camel_case_types)]
+
+#[rustc_builtin_macro]
+macro_rules! asm {
+    () => {};
+}
+
+#[lang = "sized"]
+trait Sized {}
+#[lang = "copy"]
+trait Copy {}
+
+impl Copy for i32 {}
+impl Copy for f32 {}
+impl Copy for f64 {}
+
+#[repr(simd)]
+pub struct i32x4(i32, i32, i32, i32);
+impl Copy for i32x4 {}
+
+// CHECK: rbit (r[0-9]+|fp|ip|lr), (r[0-9]+|fp|ip|lr)$
+#[no_mangle]
+pub unsafe fn reg_i32(x: i32) -> i32 {
+    let y;
+    asm!("rbit {}, {}", lateout(reg) y, in(reg) x);
+    y
+}
+
+// CHECK: vsqrt\.f32 s[0-9]+, s[0-9]+$
+#[no_mangle]
+pub unsafe fn sreg_f32(x: f32) -> f32 {
+    let y;
+    asm!("vsqrt.f32 {}, {}", lateout(sreg) y, in(sreg) x);
+    y
+}
+
+// CHECK: vneg\.f32 s([0-9]|1[0-5]), s([0-9]|1[0-5])$
+#[no_mangle]
+pub unsafe fn sreg_low16_f32(x: f32) -> f32 {
+    let y;
+    asm!("vneg.f32 {}, {}", lateout(sreg_low16) y, in(sreg_low16) x);
+    y
+}
+
+// CHECK: vsqrt\.f64 d[0-9]+, d[0-9]+$
+#[no_mangle]
+pub unsafe fn dreg_f64(x: f64) -> f64 {
+    let y;
+    asm!("vsqrt.f64 {}, {}", lateout(dreg) y, in(dreg) x);
+    y
+}
+
+// CHECK: vneg\.f64 d([0-9]|1[0-5]), d([0-9]|1[0-5])$
+#[no_mangle]
+pub unsafe fn dreg_low16_f64(x: f64) -> f64 {
+    let y;
+    asm!("vneg.f64 {}, {}", lateout(dreg_low16) y, in(dreg_low16) x);
+    y
+}
+
+// CHECK: vabs\.f64 d[0-7], d[0-7]$
+#[no_mangle]
+pub unsafe fn dreg_low8_f64(x: f64) -> f64 {
+    let y;
+    asm!("vabs.f64 {}, {}", lateout(dreg_low8) y, in(dreg_low8) x);
+    y
+}
+
+// CHECK: vmvn q[0-9]+, q[0-9]+$
+#[no_mangle]
+pub unsafe fn qreg_i32x4(x: i32x4) -> i32x4 {
+    let y;
+    asm!("vmvn {}, {}", lateout(qreg) y, in(qreg) x);
+    y
+}
+
+// CHECK: vorr q[0-7], q[0-7], q[0-7]$
+#[no_mangle]
+pub unsafe fn qreg_low8_i32x4(x: i32x4) -> i32x4 {
+    let y;
+    asm!("vorr {0}, {1}, {1}", lateout(qreg_low8) y, in(qreg_low8) x);
+    y
+}
+
+// CHECK: vand q[0-3], q[0-3], q[0-3]$
+#[no_mangle]
+pub unsafe fn qreg_low4_i32x4(x: i32x4) -> i32x4 {
+    let y;
+    asm!("vand {0}, {1}, {1}", lateout(qreg_low4) y, in(qreg_low4) x);
+    y
+}
+
+// CHECK: vswp d[0-9]+, d[0-9]+$
+#[no_mangle]
+pub unsafe fn qreg_halves(x: i32x4) -> i32x4 {
+    let y;
+    asm!("vswp {0:e}, {1:f}", lateout(qreg) y, in(qreg) x);
+    y
+}
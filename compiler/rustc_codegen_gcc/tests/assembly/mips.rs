@@ -0,0 +1,52 @@
+// compile-flags: --target mips-unknown-linux-gnu -C opt-level=2
+
+#![feature(no_core, lang_items, rustc_attrs)]
+#![no_core]
+
+#[rustc_builtin_macro]
+macro_rules! asm {
+    () => {};
+}
+
+#[lang = "sized"]
+trait Sized {}
+#[lang = "copy"]
+trait Copy {}
+
+impl Copy for i32 {}
+impl Copy for f32 {}
+impl Copy for f64 {}
+
+// CHECK: xori \$[0-9]+, \$[0-9]+, 1234$
+#[no_mangle]
+pub unsafe fn reg_i32(x: i32) -> i32 {
+    let y;
+    asm!("xori {}, {}, 1234", lateout(reg) y, in(reg) x);
+    y
+}
+
+// CHECK: sqrt\.s \$f[0-9]+, \$f[0-9]+$
+#[no_mangle]
+pub unsafe fn freg_f32(x: f32) -> f32 {
+    let y;
+    asm!("sqrt.s {}, {}", lateout(freg) y, in(freg) x);
+    y
+}
+
+// CHECK: sqrt\.d \$f[0-9]+, \$f[0-9]+$
+#[no_mangle]
+pub unsafe fn freg_f64(x: f64) -> f64 {
+    let y;
+    asm!("sqrt.d {}, {}", lateout(freg) y, in(freg) x);
+    y
+}
+
+// NOTE: x is passed in $5, so it must be moved to the explicit register.
+// CHECK: move[[:space:]]+\$2, ?\$5$
+// CHECK: xori \$2, \$2, 89$
+#[no_mangle]
+pub unsafe fn v0_i32(_unused: i32, x: i32) -> i32 {
+    let mut y = x;
+    asm!("xori $2, $2, 89", inout("$2") y);
+    y
+}
@@ -0,0 +1,42 @@
+// compile-flags: --target nvptx64-nvidia-cuda -C opt-level=2
+
+#![feature(no_core, lang_items, rustc_attrs)]
+#![no_core]
+
+#[rustc_builtin_macro]
+macro_rules! asm {
+    () => {};
+}
+
+#[lang = "sized"]
+trait Sized {}
+#[lang = "copy"]
+trait Copy {}
+
+impl Copy for i16 {}
+impl Copy for i32 {}
+impl Copy for i64 {}
+
+// CHECK: not\.b16 %r[0-9]+, %r[0-9]+;$
+#[no_mangle]
+pub unsafe fn reg16_i16(x: i16) -> i16 {
+    let y;
+    asm!("not.b16 {}, {};", lateout(reg16) y, in(reg16) x);
+    y
+}
+
+// CHECK: brev\.b32 %r[0-9]+, %r[0-9]+;$
+#[no_mangle]
+pub unsafe fn reg32_i32(x: i32) -> i32 {
+    let y;
+    asm!("brev.b32 {}, {};", lateout(reg32) y, in(reg32) x);
+    y
+}
+
+// CHECK: brev\.b64 %r[0-9]+, %r[0-9]+;$
+#[no_mangle]
+pub unsafe fn reg64_i64(x: i64) -> i64 {
+    let y;
+    asm!("brev.b64 {}, {};", lateout(reg64) y, in(reg64) x);
+    y
+}
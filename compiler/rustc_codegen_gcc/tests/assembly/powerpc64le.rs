@@ -0,0 +1,71 @@
+// compile-flags: --target powerpc64le-unknown-linux-gnu -C opt-level=2
+
+#![feature(no_core, lang_items, rustc_attrs)]
+#![no_core]
+
+#[rustc_builtin_macro]
+macro_rules! asm {
+    () => {};
+}
+
+#[lang = "sized"]
+trait Sized {}
+#[lang = "copy"]
+trait Copy {}
+
+impl Copy for i32 {}
+impl Copy for i64 {}
+impl Copy for f32 {}
+impl Copy for f64 {}
+
+// NOTE: GCC prints the registers as bare numbers.
+// CHECK: xori [0-9]+, [0-9]+, 1234$
+#[no_mangle]
+pub unsafe fn reg_i64(x: i64) -> i64 {
+    let y;
+    asm!("xori {}, {}, 1234", lateout(reg) y, in(reg) x);
+    y
+}
+
+// CHECK: xori [0-9]+, [0-9]+, 567$
+#[no_mangle]
+pub unsafe fn reg_i32(x: i32) -> i32 {
+    let y;
+    asm!("xori {}, {}, 567", lateout(reg) y, in(reg) x);
+    y
+}
+
+// NOTE: r0 reads as zero in the base register of addi, so it must not be allocated.
+// CHECK: addi [0-9]+, ([1-9]|[12][0-9]|3[01]), 1234$
+#[no_mangle]
+pub unsafe fn reg_nonzero_i64(x: i64) -> i64 {
+    let y;
+    asm!("addi {}, {}, 1234", lateout(reg) y, in(reg_nonzero) x);
+    y
+}
+
+// CHECK: fnabs [0-9]+, [0-9]+$
+#[no_mangle]
+pub unsafe fn freg_f64(x: f64) -> f64 {
+    let y;
+    asm!("fnabs {}, {}", lateout(freg) y, in(freg) x);
+    y
+}
+
+// CHECK: fabs [0-9]+, [0-9]+$
+#[no_mangle]
+pub unsafe fn freg_f32(x: f32) -> f32 {
+    let y;
+    asm!("fabs {}, {}", lateout(freg) y, in(freg) x);
+    y
+}
+
+// NOTE: x is passed in r4, so it must be moved to the explicit register.
+// CHECK: mr[[:space:]]+3, ?4$
+// CHECK: xori 3, 3, 89$
+#[no_mangle]
+pub unsafe fn r3_i64(_unused: i64, x: i64) -> i64 {
+    let mut y = x;
+    asm!("xori 3, 3, 89", inout("r3") y);
+    y
+}
@@ -0,0 +1,61 @@
+// compile-flags: --target riscv64gc-unknown-linux-gnu -C opt-level=2
+
+#![feature(no_core, lang_items, rustc_attrs)]
+#![no_core]
+
+#[rustc_builtin_macro]
+macro_rules! asm {
+    () => {};
+}
+
+#[lang = "sized"]
+trait Sized {}
+#[lang = "copy"]
+trait Copy {}
+
+impl Copy for i32 {}
+impl Copy for i64 {}
+impl Copy for f32 {}
+impl Copy for f64 {}
+
+// CHECK: xori (zero|ra|sp|gp|tp|[ast][0-9]+), (zero|ra|sp|gp|tp|[ast][0-9]+), 1234$
+#[no_mangle]
+pub unsafe fn reg_i64(x: i64) -> i64 {
+    let y;
+    asm!("xori {}, {}, 1234", lateout(reg) y, in(reg) x);
+    y
+}
+
+// CHECK: xori (zero|ra|sp|gp|tp|[ast][0-9]+), (zero|ra|sp|gp|tp|[ast][0-9]+), 567$
+#[no_mangle]
+pub unsafe fn reg_i32(x: i32) -> i32 {
+    let y;
+    asm!("xori {}, {}, 567", lateout(reg) y, in(reg) x);
+    y
+}
+
+// CHECK: fsqrt\.s f[ast][0-9]+, f[ast][0-9]+$
+#[no_mangle]
+pub unsafe fn freg_f32(x: f32) -> f32 {
+    let y;
+    asm!("fsqrt.s {}, {}", lateout(freg) y, in(freg) x);
+    y
+}
+
+// CHECK: fsqrt\.d f[ast][0-9]+, f[ast][0-9]+$
+#[no_mangle]
+pub unsafe fn freg_f64(x: f64) -> f64 {
+    let y;
+    asm!("fsqrt.d {}, {}", lateout(freg) y, in(freg) x);
+    y
+}
+
+// NOTE: x is passed in a1, so it must be moved to the explicit register.
+// CHECK: mv[[:space:]]+a0, ?a1$
+// CHECK: xori a0, a0, 89$
+#[no_mangle]
+pub unsafe fn a0_i64(_unused: i64, x: i64) -> i64 {
+    let mut y = x;
+    asm!("xori a0, a0, 89", inout("a0") y);
+    y
+}
@@ -0,0 +1,26 @@
+// compile-flags: --target riscv64gc-unknown-linux-gnu -C opt-level=2 -C target-feature=+v
+
+#![feature(no_core, lang_items, rustc_attrs)]
+#![no_core]
+
+#[rustc_builtin_macro]
+macro_rules! asm {
+    () => {};
+}
+
+#[lang = "sized"]
+trait Sized {}
+#[lang = "copy"]
+trait Copy {}
+
+// CHECK: vmv\.v\.i v0, 7$
+#[no_mangle]
+pub unsafe fn v0_clobber() {
+    asm!("vmv.v.i v0, 7", out("v0") _);
+}
+
+// CHECK: vid\.v v31$
+#[no_mangle]
+pub unsafe fn v31_clobber_abi() {
+    asm!("vid.v v31", clobber_abi("C"));
+}
@@ -0,0 +1,61 @@
+// compile-flags: --target s390x-unknown-linux-gnu -C opt-level=2
+
+#![feature(no_core, lang_items, rustc_attrs)]
+#![no_core]
+
+#[rustc_builtin_macro]
+macro_rules! asm {
+    () => {};
+}
+
+#[lang = "sized"]
+trait Sized {}
+#[lang = "copy"]
+trait Copy {}
+
+impl Copy for i32 {}
+impl Copy for i64 {}
+impl Copy for f32 {}
+impl Copy for f64 {}
+
+// CHECK: lrvgr %r[0-9]+, %r[0-9]+$
+#[no_mangle]
+pub unsafe fn reg_i64(x: i64) -> i64 {
+    let y;
+    asm!("lrvgr {}, {}", lateout(reg) y, in(reg) x);
+    y
+}
+
+// CHECK: lrvr %r[0-9]+, %r[0-9]+$
+#[no_mangle]
+pub unsafe fn reg_i32(x: i32) -> i32 {
+    let y;
+    asm!("lrvr {}, {}", lateout(reg) y, in(reg) x);
+    y
+}
+
+// CHECK: sqebr %f[0-9]+, %f[0-9]+$
+#[no_mangle]
+pub unsafe fn freg_f32(x: f32) -> f32 {
+    let y;
+    asm!("sqebr {}, {}", lateout(freg) y, in(freg) x);
+    y
+}
+
+// CHECK: sqdbr %f[0-9]+, %f[0-9]+$
+#[no_mangle]
+pub unsafe fn freg_f64(x: f64) -> f64 {
+    let y;
+    asm!("sqdbr {}, {}", lateout(freg) y, in(freg) x);
+    y
+}
+
+// NOTE: x is passed in r3, so it must be moved to the explicit register.
+// CHECK: lgr[[:space:]]+%r2, ?%r3$
+// CHECK: lrvgr %r2, %r2$
+#[no_mangle]
+pub unsafe fn r2_i64(_unused: i64, x: i64) -> i64 {
+    let mut y = x;
+    asm!("lrvgr %r2, %r2", inout("r2") y);
+    y
+}
@@ -0,0 +1,46 @@
+// compile-flags: --target x86_64-unknown-linux-gnu -C opt-level=2
+
+#![feature(no_core, lang_items, link_llvm_intrinsics, rustc_attrs)]
+#![no_core]
+
+#[rustc_builtin_macro]
+macro_rules! asm {
+    () => {};
+}
+
+#[lang = "sized"]
+trait Sized {}
+#[lang = "copy"]
+trait Copy {}
+
+impl Copy for i64 {}
+
+// NOTE: x lives in memory since its address is given to the asm block, which may push: the red zone
+// is disabled so its slot must be allocated below the stack pointer.
+// CHECK: sub[[:space:]]+rsp, [0-9]+$
+// CHECK: push rbx$
+#[no_mangle]
+pub unsafe fn red_zone(mut x: i64) -> i64 {
+    asm!("push rbx", "pop rbx", "inc qword ptr [{}]", in(reg) &mut x as *mut i64);
+    x
+}
+
+// CHECK: bswap r[a-z0-9]+$
+#[no_mangle]
+pub unsafe fn nostack(x: i64) -> i64 {
+    let y;
+    asm!("bswap {}", inout(reg) x => y, options(nostack));
+    y
+}
+
+extern "C" {
+    #[link_name = "llvm.x86.sse2.pause"]
+    fn pause();
+}
+
+// NOTE: this is the intrinsic called by `core::hint::spin_loop`.
+// CHECK: pause$
+#[no_mangle]
+pub unsafe fn spin_loop() {
+    pause();
+}
@@ -0,0 +1,89 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, start)]
+#![allow(clashing_extern_declarations)]
+
+#![no_std]
+
+use core::hint::black_box;
+
+#[panic_handler]
+fn panic_handler(_: &core::panic::PanicInfo) -> ! {
+    core::intrinsics::abort();
+}
+
+/*
+ * Code
+ */
+
+extern "C" fn not(value: bool) -> bool {
+    !value
+}
+
+extern "C" fn widen_signed(value: i8) -> i64 {
+    value as i64
+}
+
+extern "C" fn widen_unsigned(value: u8) -> u64 {
+    value as u64
+}
+
+extern "C" fn widen_u16(value: u16) -> u32 {
+    value as u32
+}
+
+extern "C" fn narrow(value: i32) -> i16 {
+    value as i16
+}
+
+#[no_mangle]
+extern "C" fn bool_to_u32(value: bool) -> u32 {
+    value as u32
+}
+
+#[no_mangle]
+extern "C" fn u8_to_u32(value: u8) -> u32 {
+    value as u32
+}
+
+// NOTE: these declarations reuse the functions above, so the arguments have to be converted
+// between bool and u8 in the calls.
+extern "C" {
+    #[link_name = "bool_to_u32"]
+    fn bool_to_u32_from_u8(value: u8) -> u32;
+    #[link_name = "u8_to_u32"]
+    fn u8_to_u32_from_bool(value: bool) -> u32;
+}
+
+#[start]
+fn main(_argc: isize, _argv: *const *const u8) -> isize {
+    let not_fn: extern "C" fn(bool) -> bool = black_box(not);
+    assert_eq!(not_fn(black_box(true)), false);
+    assert_eq!(not_fn(black_box(false)), true);
+    assert_eq!(not_fn(black_box(false)) as u8, 1);
+
+    let signed: extern "C" fn(i8) -> i64 = black_box(widen_signed);
+    assert_eq!(signed(black_box(-1)), -1);
+    assert_eq!(signed(black_box(i8::MIN)), -128);
+
+    let unsigned: extern "C" fn(u8) -> u64 = black_box(widen_unsigned);
+    assert_eq!(unsigned(black_box(0xff)), 0xff);
+
+    let unsigned16: extern "C" fn(u16) -> u32 = black_box(widen_u16);
+    assert_eq!(unsigned16(black_box(0x8000)), 0x8000);
+
+    let narrow_fn: extern "C" fn(i32) -> i16 = black_box(narrow);
+    assert_eq!(narrow_fn(black_box(-2)) as i64, -2);
+    assert_eq!(narrow_fn(black_box(0x1_8000)) as i32, -0x8000);
+
+    unsafe {
+        assert_eq!(bool_to_u32_from_u8(black_box(0)), 0);
+        assert_eq!(bool_to_u32_from_u8(black_box(1)), 1);
+        assert_eq!(u8_to_u32_from_bool(black_box(false)), 0);
+        assert_eq!(u8_to_u32_from_bool(black_box(true)), 1);
+    }
+    0
+}
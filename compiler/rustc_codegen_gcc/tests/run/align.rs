@@ -0,0 +1,75 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, start)]
+
+#![no_std]
+
+use core::hint::black_box;
+
+#[panic_handler]
+fn panic_handler(_: &core::panic::PanicInfo) -> ! {
+    core::intrinsics::abort();
+}
+
+/*
+ * Code
+ */
+
+#[repr(align(64))]
+#[derive(Clone, Copy)]
+struct Buffer([f32; 16]);
+
+#[repr(C, align(32))]
+struct Small(u8);
+
+static STATIC_BUFFER: Buffer = Buffer([1.0; 16]);
+static mut MUTABLE_BUFFER: Buffer = Buffer([0.0; 16]);
+static STATIC_SMALL: Small = Small(3);
+
+fn is_aligned<T>(value: &T, align: usize) -> bool {
+    value as *const T as usize % align == 0
+}
+
+#[inline(never)]
+fn by_value(buffer: Buffer) -> f32 {
+    assert!(is_aligned(&buffer, 64));
+    buffer.0.iter().sum()
+}
+
+#[inline(never)]
+fn by_ref(buffer: &Buffer) -> f32 {
+    assert!(is_aligned(buffer, 64));
+    buffer.0.iter().sum()
+}
+
+#[start]
+fn main(_argc: isize, _argv: *const *const u8) -> isize {
+    assert_eq!(core::mem::align_of::<Buffer>(), 64);
+    assert_eq!(core::mem::size_of::<Small>(), 32);
+
+    assert!(is_aligned(&STATIC_BUFFER, 64));
+    assert!(is_aligned(unsafe { &MUTABLE_BUFFER }, 64));
+    assert!(is_aligned(&STATIC_SMALL, 32));
+    assert_eq!(STATIC_SMALL.0, 3);
+
+    let padding = black_box(1u8);
+    let local = black_box(Buffer([2.0; 16]));
+    assert!(is_aligned(&padding, 1));
+    assert!(is_aligned(&local, 64));
+
+    let locals = [local; 3];
+    for buffer in &locals {
+        assert!(is_aligned(buffer, 64));
+    }
+
+    assert_eq!(by_value(local), 32.0);
+    assert_eq!(by_ref(&STATIC_BUFFER), 16.0);
+    unsafe {
+        MUTABLE_BUFFER.0[0] = 4.0;
+        assert_eq!(by_ref(&MUTABLE_BUFFER), 4.0);
+    }
+    0
+}
@@ -6,6 +6,8 @@
 #![feature(asm_const, asm_sym)]
 
 use std::arch::{asm, global_asm};
+use std::arch::x86_64::{__m128i, __m256i, _mm_set_epi64x, _mm256_set1_epi32};
+use std::mem::transmute;
 
 global_asm!("
     .global add_asm
@@ -29,6 +31,16 @@ pub unsafe fn mem_cpy(dst: *mut u8, src: *const u8, len: usize) {
     );
 }
 
+#[target_feature(enable = "avx2")]
+unsafe fn add_ymm(a: __m256i, b: __m256i) -> __m256i {
+    let res: __m256i;
+    asm!("vpaddd ymm0, ymm0, ymm1",
+        inout("ymm0") a => res,
+        in("ymm1") b,
+    );
+    res
+}
+
 fn main() {
     unsafe {
         asm!("nop");
@@ -161,6 +173,28 @@ extern "C" fn foo() -> u64 { 42 }
     }
     assert_eq!(x, 42);
 
+    // check pure readonly asm
+    let mut value: u64 = 42;
+    let x: u64;
+    unsafe {
+        asm!("mov {}, qword ptr [{}]",
+            out(reg) x,
+            in(reg) &value,
+            options(pure, readonly, nostack)
+        );
+    }
+    assert_eq!(x, 42);
+    value = 43;
+    let x: u64;
+    unsafe {
+        asm!("mov {}, qword ptr [{}]",
+            out(reg) x,
+            in(reg) &value,
+            options(pure, readonly, nostack)
+        );
+    }
+    assert_eq!(x, 43);
+
     assert_eq!(unsafe { add_asm(40, 2) }, 42);
 
     let array1 = [1u8, 2, 3];
@@ -169,4 +203,29 @@ extern "C" fn foo() -> u64 { 42 }
         mem_cpy(array2.as_mut_ptr(), array1.as_ptr(), 3);
     }
     assert_eq!(array1, array2);
+
+    // check explicit vector registers
+    let a = unsafe { _mm_set_epi64x(1, 2) };
+    let b = unsafe { _mm_set_epi64x(3, 4) };
+    let res: __m128i;
+    unsafe {
+        asm!("paddq xmm0, xmm1",
+            inout("xmm0") a => res,
+            in("xmm1") b,
+        );
+    }
+    assert_eq!(unsafe { transmute::<_, [u64; 2]>(res) }, [6, 4]);
+
+    let res = unsafe { add_ymm(_mm256_set1_epi32(1), _mm256_set1_epi32(2)) };
+    assert_eq!(unsafe { transmute::<_, [u32; 8]>(res) }, [3; 8]);
+
+    // check 128-bit integers in vector registers
+    let x: u128 = 1 << 64 | 2;
+    let res: u128;
+    unsafe {
+        asm!("paddq {0}, {0}",
+            inout(xmm_reg) x => res,
+        );
+    }
+    assert_eq!(res, 2 << 64 | 4);
 }
@@ -0,0 +1,74 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, start)]
+
+#![no_std]
+
+use core::sync::atomic::{AtomicI16, AtomicI32, AtomicI64, AtomicI8, AtomicU32, AtomicU64, AtomicU8, Ordering, compiler_fence, fence};
+
+#[panic_handler]
+fn panic_handler(_: &core::panic::PanicInfo) -> ! {
+    core::intrinsics::abort();
+}
+
+/*
+ * Code
+ */
+
+#[start]
+fn main(_argc: isize, _argv: *const *const u8) -> isize {
+    let value = AtomicI32::new(-5);
+    assert_eq!(value.fetch_max(3, Ordering::SeqCst), -5);
+    assert_eq!(value.fetch_max(-10, Ordering::SeqCst), 3);
+    assert_eq!(value.fetch_min(-10, Ordering::SeqCst), 3);
+    assert_eq!(value.fetch_min(0, Ordering::SeqCst), -10);
+    assert_eq!(value.load(Ordering::SeqCst), -10);
+
+    let value = AtomicU32::new(5);
+    assert_eq!(value.fetch_max(u32::MAX, Ordering::AcqRel), 5);
+    assert_eq!(value.fetch_min(7, Ordering::Release), u32::MAX);
+    assert_eq!(value.load(Ordering::Relaxed), 7);
+
+    let value = AtomicI8::new(-128);
+    assert_eq!(value.fetch_max(127, Ordering::SeqCst), -128);
+    assert_eq!(value.load(Ordering::SeqCst), 127);
+
+    let value = AtomicU8::new(0b1100);
+    assert_eq!(value.fetch_nand(0b1010, Ordering::SeqCst), 0b1100);
+    assert_eq!(value.load(Ordering::SeqCst), !0b1000);
+
+    let value = AtomicI16::new(100);
+    assert_eq!(value.fetch_min(-100, Ordering::Acquire), 100);
+    assert_eq!(value.load(Ordering::SeqCst), -100);
+
+    let value = AtomicI64::new(i64::MIN);
+    assert_eq!(value.fetch_max(-1, Ordering::SeqCst), i64::MIN);
+    assert_eq!(value.fetch_nand(-1, Ordering::SeqCst), -1);
+    assert_eq!(value.load(Ordering::SeqCst), 0);
+
+    let value = AtomicU32::new(0);
+    assert_eq!(value.compare_exchange(1, 2, Ordering::Relaxed, Ordering::SeqCst), Err(0));
+    assert_eq!(value.compare_exchange(0, 2, Ordering::Release, Ordering::Acquire), Ok(0));
+    let mut current = value.load(Ordering::Relaxed);
+    loop {
+        match value.compare_exchange_weak(current, current * 3, Ordering::AcqRel, Ordering::Relaxed) {
+            Ok(_) => break,
+            Err(actual) => current = actual,
+        }
+    }
+    assert_eq!(value.load(Ordering::SeqCst), 6);
+
+    let value = AtomicU64::new(1 << 63);
+    assert_eq!(value.fetch_min(1, Ordering::SeqCst), 1 << 63);
+    assert_eq!(value.fetch_max(1 << 62, Ordering::SeqCst), 1);
+    assert_eq!(value.load(Ordering::SeqCst), 1 << 62);
+
+    for ordering in [Ordering::Acquire, Ordering::Release, Ordering::AcqRel, Ordering::SeqCst] {
+        fence(ordering);
+        compiler_fence(ordering);
+    }
+    0
+}
@@ -0,0 +1,67 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, start)]
+
+#![no_std]
+
+use core::hint::black_box;
+
+#[panic_handler]
+fn panic_handler(_: &core::panic::PanicInfo) -> ! {
+    core::intrinsics::abort();
+}
+
+/*
+ * Code
+ */
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Large {
+    values: [u64; 5],
+}
+
+#[repr(C, align(32))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Aligned {
+    values: [u32; 9],
+}
+
+#[inline(never)]
+extern "C" fn sum(value: Large) -> u64 {
+    value.values.iter().sum()
+}
+
+#[inline(never)]
+extern "C" fn modify(mut value: Large) -> u64 {
+    value.values[0] = 100;
+    value.values[0]
+}
+
+#[inline(never)]
+extern "C" fn check_aligned(before: u8, value: Aligned, after: u8) -> u32 {
+    assert_eq!(&value as *const Aligned as usize % 32, 0);
+    before as u32 + value.values.iter().sum::<u32>() + after as u32
+}
+
+#[start]
+fn main(_argc: isize, _argv: *const *const u8) -> isize {
+    let large = black_box(Large { values: [1, 2, 3, 4, 5] });
+    assert_eq!(sum(large), 15);
+
+    // The callee works on its own copy.
+    assert_eq!(modify(large), 100);
+    assert_eq!(large.values[0], 1);
+
+    let sum_fn: extern "C" fn(Large) -> u64 = black_box(sum);
+    assert_eq!(sum_fn(large), 15);
+
+    let aligned = black_box(Aligned { values: [1; 9] });
+    assert_eq!(check_aligned(1, aligned, 2), 12);
+    let check_fn: extern "C" fn(u8, Aligned, u8) -> u32 = black_box(check_aligned);
+    assert_eq!(check_fn(3, aligned, 4), 16);
+    0
+}
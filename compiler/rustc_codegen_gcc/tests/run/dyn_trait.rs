@@ -0,0 +1,71 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, start)]
+
+#![no_std]
+
+#[panic_handler]
+fn panic_handler(_: &core::panic::PanicInfo) -> ! {
+    core::intrinsics::abort();
+}
+
+/*
+ * Code
+ */
+
+trait Shape {
+    fn area(&self) -> u32;
+    fn sides(&self) -> u32;
+}
+
+struct Square(u32);
+
+impl Shape for Square {
+    fn area(&self) -> u32 {
+        self.0 * self.0
+    }
+
+    fn sides(&self) -> u32 {
+        4
+    }
+}
+
+struct Rectangle(u32, u32);
+
+impl Shape for Rectangle {
+    fn area(&self) -> u32 {
+        self.0 * self.1
+    }
+
+    fn sides(&self) -> u32 {
+        4
+    }
+}
+
+struct Triangle(u32, u32);
+
+impl Shape for Triangle {
+    fn area(&self) -> u32 {
+        self.0 * self.1 / 2
+    }
+
+    fn sides(&self) -> u32 {
+        3
+    }
+}
+
+#[start]
+fn main(_argc: isize, _argv: *const *const u8) -> isize {
+    let shapes: [&dyn Shape; 3] = [&Square(3), &Rectangle(2, 5), &Triangle(4, 3)];
+    let area: u32 = shapes.iter().map(|shape| shape.area()).sum();
+    let sides: u32 = shapes.iter().map(|shape| shape.sides()).sum();
+    assert_eq!(area, 25);
+    assert_eq!(sides, 11);
+
+    let square: &dyn Shape = &Square(5);
+    assert_eq!(square.area(), 25);
+    0
+}
@@ -0,0 +1,106 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, start)]
+
+#![no_std]
+
+use core::hint::black_box;
+use core::intrinsics::{
+    ceilf32, ceilf64, copysignf32, copysignf64, cosf64, exp2f64, expf32, fabsf32, fabsf64,
+    fadd_fast, fdiv_fast, floorf32, floorf64, fmaf32, fmaf64, fmul_fast, frem_fast, fsub_fast,
+    log10f64, log2f32, logf32, maxnumf32, maxnumf64, minnumf32, minnumf64, powf64, powif32,
+    powif64, roundf32, roundf64, sinf32, sqrtf32, sqrtf64, truncf32, truncf64,
+};
+
+#[panic_handler]
+fn panic_handler(_: &core::panic::PanicInfo) -> ! {
+    core::intrinsics::abort();
+}
+
+/*
+ * Code
+ */
+
+#[start]
+fn main(_argc: isize, _argv: *const *const u8) -> isize {
+    let a = black_box(2.25_f32);
+    let b = black_box(-3.5_f64);
+
+    unsafe {
+        assert_eq!(sqrtf32(a), 1.5);
+        assert_eq!(sqrtf64(b * b), 3.5);
+        assert_eq!(powif32(a, 2), 5.0625);
+        assert_eq!(powif64(b, -1), 1.0 / -3.5);
+        assert_eq!(fmaf32(a, 2.0, 0.5), 5.0);
+        assert_eq!(fmaf64(b, 2.0, 0.5), -6.5);
+        assert_eq!(fabsf32(-a), 2.25);
+        assert_eq!(fabsf64(b), 3.5);
+    }
+
+    // Transcendental functions.
+    unsafe {
+        assert_eq!(sinf32(black_box(0.0)), 0.0);
+        assert_eq!(cosf64(black_box(0.0)), 1.0);
+        assert_eq!(expf32(black_box(0.0)), 1.0);
+        assert_eq!(exp2f64(black_box(3.0)), 8.0);
+        assert_eq!(logf32(black_box(1.0)), 0.0);
+        assert_eq!(log10f64(black_box(1000.0)), 3.0);
+        assert_eq!(log2f32(black_box(8.0)), 3.0);
+        assert_eq!(powf64(black_box(2.0), 10.0), 1024.0);
+    }
+
+    // Rounding.
+    unsafe {
+        assert_eq!(floorf32(a), 2.0);
+        assert_eq!(floorf64(b), -4.0);
+        assert_eq!(ceilf32(a), 3.0);
+        assert_eq!(ceilf64(b), -3.0);
+        assert_eq!(truncf32(a), 2.0);
+        assert_eq!(truncf64(b), -3.0);
+        assert_eq!(roundf32(black_box(2.5)), 3.0);
+        assert_eq!(roundf64(b), -4.0);
+    }
+    assert_eq!(unsafe { core::intrinsics::rintf32(black_box(2.5)) }, 2.0);
+    assert_eq!(unsafe { core::intrinsics::nearbyintf64(black_box(-3.5)) }, -4.0);
+
+    unsafe {
+        assert_eq!(copysignf32(a, -1.0), -2.25);
+        assert_eq!(copysignf64(b, 1.0), 3.5);
+        assert_eq!(minnumf32(a, -1.0), -1.0);
+        assert_eq!(minnumf64(b, f64::NAN), -3.5);
+        assert_eq!(maxnumf32(a, f32::NAN), 2.25);
+        assert_eq!(maxnumf64(b, 0.0), 0.0);
+        assert!(minnumf32(f32::NAN, f32::NAN).is_nan());
+        assert_eq!(maxnumf32(f32::NAN, a), 2.25);
+        assert!(minnumf32(black_box(0.0), -0.0).is_sign_negative());
+        assert!(minnumf64(black_box(-0.0), 0.0).is_sign_negative());
+        assert!(maxnumf32(black_box(-0.0), 0.0).is_sign_positive());
+        assert!(maxnumf64(black_box(0.0), -0.0).is_sign_positive());
+    }
+
+    unsafe {
+        assert_eq!(fadd_fast(a, 1.0), 3.25);
+        assert_eq!(fsub_fast(b, 1.0), -4.5);
+        assert_eq!(fmul_fast(a, 4.0), 9.0);
+        assert_eq!(fdiv_fast(b, 2.0), -1.75);
+        assert_eq!(frem_fast(a, 1.0), 0.25);
+    }
+
+    // Saturating float to int casts.
+    assert_eq!(black_box(f32::NAN) as u8, 0);
+    assert_eq!(black_box(f32::NAN) as u32, 0);
+    assert_eq!(black_box(f64::NAN) as i64, 0);
+    assert_eq!(black_box(f64::NAN) as u128, 0);
+    assert_eq!(black_box(-1.5_f32) as u32, 0);
+    assert_eq!(black_box(300.0_f32) as u8, u8::MAX);
+    assert_eq!(black_box(-300.0_f32) as i8, i8::MIN);
+    assert_eq!(black_box(f32::INFINITY) as i32, i32::MAX);
+    assert_eq!(black_box(f64::NEG_INFINITY) as i64, i64::MIN);
+    assert_eq!(black_box(1e40_f64) as u128, u128::MAX);
+    assert_eq!(black_box(-2.9_f64) as i16, -2);
+    assert_eq!(unsafe { black_box(42.9_f32).to_int_unchecked::<u32>() }, 42);
+    0
+}
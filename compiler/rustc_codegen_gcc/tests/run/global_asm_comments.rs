@@ -0,0 +1,51 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, start)]
+
+#![no_std]
+
+use core::arch::global_asm;
+
+#[panic_handler]
+fn panic_handler(_: &core::panic::PanicInfo) -> ! {
+    core::intrinsics::abort();
+}
+
+/*
+ * Code
+ */
+
+global_asm!(r#"
+    .section .rodata
+    .global comment_string
+comment_string:
+    .asciz "a // b" // the slashes in the string are kept
+    .global comment_chars
+comment_chars:
+    .byte '", '/, '\\ // neither the quote nor the slash start anything
+    .text
+    .global comment_add
+comment_add:
+    mov rax, rdi # the assembler removes this comment // not the backend
+    add rax, rsi // add the operands
+    ret
+"#);
+
+extern "C" {
+    static comment_string: [u8; 7];
+    static comment_chars: [u8; 3];
+    fn comment_add(a: i64, b: i64) -> i64;
+}
+
+#[start]
+fn main(_argc: isize, _argv: *const *const u8) -> isize {
+    unsafe {
+        assert_eq!(&comment_string, b"a // b\0");
+        assert_eq!(comment_chars, [b'"', b'/', b'\\']);
+        assert_eq!(comment_add(40, 2), 42);
+    }
+    0
+}
@@ -31,6 +31,17 @@ macro_rules! check {
 
     check!(u32, (2220326408_u32 + black_box(1)) >> (32 - 6));
 
+    // Saturating arithmetic on integer types narrower than 32 bits.
+    check!(i8, 100_i8.saturating_add(black_box(100)));
+    check!(i8, (-100_i8).saturating_add(black_box(-100)));
+    check!(i8, 100_i8.saturating_sub(black_box(-100)));
+    check!(i8, (-100_i8).saturating_sub(black_box(100)));
+    check!(i8, 10_i8.saturating_add(black_box(-20)));
+    check!(i16, 30000_i16.saturating_add(black_box(30000)));
+    check!(i16, (-30000_i16).saturating_sub(black_box(30000)));
+    check!(u8, 200_u8.saturating_add(black_box(100)));
+    check!(u16, 100_u16.saturating_sub(black_box(200)));
+
     /// Generate `check!` tests for integer types at least as wide as 128 bits.
     macro_rules! check_ops128 {
         () => {
@@ -46,6 +57,34 @@ macro_rules! check_ops128 {
             check!(T, VAL2 >> black_box(64));
             check!(T, VAL3 >> black_box(64));
             check!(T, VAL3 >> black_box(81));
+
+            // Division and remainder by values wider than 64 bits.
+            check!(T, VAL3 / black_box(VAL3 >> 32));
+            check!(T, VAL3 % black_box(VAL3 >> 32));
+            check!(T, VAL4 / black_box(VAL3 >> 64));
+            check!(T, VAL4 % black_box(VAL3 >> 64));
+            check!(Option<T>, VAL4.checked_div(black_box(T::MAX)));
+            check!(Option<T>, VAL4.checked_rem(black_box(T::MAX)));
+
+            // Comparisons.
+            check!(bool, VAL3 > black_box(VAL4));
+            check!(bool, VAL4 < black_box(VAL3));
+            check!(bool, VAL3 >= black_box(VAL3));
+            check!(bool, VAL1 <= black_box(VAL2));
+            check!(bool, VAL3 == black_box(VAL3));
+            check!(bool, VAL3 != black_box(VAL4));
+            check!(bool, T::MIN < black_box(VAL1));
+            check!(bool, T::MAX > black_box(VAL3));
+
+            // Casts.
+            check!(u128, black_box(VAL3) as u128);
+            check!(i128, black_box(VAL3) as i128);
+            check!(T, black_box(-1_i64) as T);
+            check!(T, black_box(u64::MAX) as T);
+            check!(u32, black_box(VAL3) as u32);
+            check!(i64, black_box(VAL3) as i64);
+            check!(f64, black_box(VAL3) as f64);
+            check!(T, black_box(1e30_f64) as T);
         };
     }
 
@@ -109,6 +148,20 @@ macro_rules! check_ops32 {
             check!(Option<T>, VAL1.checked_shl(black_box(64)));
             check!(Option<T>, VAL1.checked_shl(black_box(81)));
 
+            // Rotations.
+            check!(T, VAL1.rotate_left(black_box(0)));
+            check!(T, VAL1.rotate_left(black_box(1)));
+            check!(T, VAL1.rotate_left(black_box(31)));
+            check!(T, VAL1.rotate_left(black_box(81)));
+            check!(T, VAL2.rotate_left(black_box(7)));
+            check!(T, VAL3.rotate_left(black_box(13)));
+            check!(T, VAL1.rotate_right(black_box(0)));
+            check!(T, VAL1.rotate_right(black_box(1)));
+            check!(T, VAL1.rotate_right(black_box(31)));
+            check!(T, VAL1.rotate_right(black_box(81)));
+            check!(T, VAL2.rotate_right(black_box(7)));
+            check!(T, VAL3.rotate_right(black_box(13)));
+
             check!(T, VAL1 >> black_box(0));
             check!(T, VAL1 >> black_box(1));
 
@@ -274,6 +327,23 @@ macro_rules! check_ops32 {
             check!(T, VAL4.saturating_div(black_box(2)));
             check!(T, VAL5.saturating_div(black_box(2)));
             check!(T, (T::MIN).saturating_div((0 as T).wrapping_sub(black_box(1))));
+
+            check!(Option<T>, VAL1.checked_rem(black_box(2)));
+            check!(Option<T>, VAL1.checked_rem(black_box(1) + VAL2));
+            check!(Option<T>, VAL3.checked_rem(VAL3));
+            check!(Option<T>, VAL4.checked_rem(black_box(7)));
+            check!(Option<T>, VAL5.checked_rem(black_box(7)));
+            check!(Option<T>, (T::MIN).checked_rem(black_box(0 as T).wrapping_sub(1)));
+            check!(Option<T>, VAL5.checked_rem(black_box(0))); // var5 % 0
+
+            check!(T, VAL1.wrapping_rem(black_box(2)));
+            check!(T, VAL3.wrapping_rem(VAL3));
+            check!(T, VAL4.wrapping_rem(black_box(7)));
+            check!(T, VAL5.wrapping_rem(black_box(7)));
+            check!(T, (T::MIN).wrapping_rem(black_box(0 as T).wrapping_sub(1)));
+
+            check!((T, bool), VAL4.overflowing_rem(black_box(7)));
+            check!((T, bool), (T::MIN).overflowing_rem(black_box(0 as T).wrapping_sub(1)));
         };
     }
 
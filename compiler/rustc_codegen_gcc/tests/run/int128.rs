@@ -0,0 +1,71 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, start)]
+
+#![no_std]
+
+use core::hint::black_box;
+use core::mem::{align_of, size_of, MaybeUninit};
+use core::ptr::addr_of;
+
+#[panic_handler]
+fn panic_handler(_: &core::panic::PanicInfo) -> ! {
+    core::intrinsics::abort();
+}
+
+/*
+ * Code
+ */
+
+#[repr(C)]
+struct WithInt128 {
+    tag: u8,
+    value: u128,
+}
+
+// NOTE: this offset is computed by the const evaluator of rustc, while the offsets and addresses
+// observed at run-time below come from the layout of the GCC types.
+const RUSTC_OFFSET: usize = unsafe {
+    let value = MaybeUninit::<WithInt128>::uninit();
+    let base = value.as_ptr();
+    (addr_of!((*base).value) as *const u8).offset_from(base as *const u8) as usize
+};
+
+extern "C" fn add(a: u128, b: i128) -> u128 {
+    a.wrapping_add(b as u128)
+}
+
+extern "C" fn read(value: &WithInt128) -> u128 {
+    value.value
+}
+
+extern "C" fn field_offset(value: &WithInt128) -> usize {
+    addr_of!(value.value) as usize - value as *const WithInt128 as usize
+}
+
+#[start]
+fn main(_argc: isize, _argv: *const *const u8) -> isize {
+    assert_eq!(RUSTC_OFFSET, align_of::<u128>());
+
+    let values = black_box([WithInt128 { tag: 1, value: u128::MAX }, WithInt128 { tag: 2, value: 1 << 100 }]);
+    let offset_fn: extern "C" fn(&WithInt128) -> usize = black_box(field_offset);
+    assert_eq!(offset_fn(&values[0]), RUSTC_OFFSET);
+    let stride = &values[1] as *const WithInt128 as usize - &values[0] as *const WithInt128 as usize;
+    assert_eq!(stride, size_of::<WithInt128>());
+    for value in &values {
+        assert_eq!(value as *const WithInt128 as usize % align_of::<WithInt128>(), 0);
+    }
+
+    let read_fn: extern "C" fn(&WithInt128) -> u128 = black_box(read);
+    assert_eq!(values[1].tag, 2);
+    assert_eq!(read_fn(&values[0]), u128::MAX);
+    assert_eq!(read_fn(&values[1]), 1 << 100);
+
+    let add_fn: extern "C" fn(u128, i128) -> u128 = black_box(add);
+    assert_eq!(add_fn(black_box(1 << 64), black_box(-1)), u64::MAX as u128);
+    assert_eq!(add_fn(black_box(u128::MAX), black_box(2)), 1);
+    0
+}
@@ -0,0 +1,74 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, start)]
+
+#![no_std]
+
+#[panic_handler]
+fn panic_handler(_: &core::panic::PanicInfo) -> ! {
+    core::intrinsics::abort();
+}
+
+/*
+ * Code
+ */
+
+#[cfg(target_arch = "aarch64")]
+fn check_neon() {
+    use core::arch::aarch64::*;
+    use core::mem::transmute;
+
+    unsafe {
+        let a: int8x8_t = transmute([1i8, -2, 3, -4, 5, -6, 7, -8]);
+        let b: int8x8_t = transmute([-1i8, 2, -3, 4, -5, 6, -7, 8]);
+        let max: [i8; 8] = transmute(vmax_s8(a, b));
+        assert_eq!(max, [1, 2, 3, 4, 5, 6, 7, 8]);
+        let min: [i8; 8] = transmute(vmin_s8(a, b));
+        assert_eq!(min, [-1, -2, -3, -4, -5, -6, -7, -8]);
+        let abs: [i8; 8] = transmute(vabs_s8(a));
+        assert_eq!(abs, [1, 2, 3, 4, 5, 6, 7, 8]);
+
+        let a: uint16x8_t = transmute([1u16, 20, 3, 40, 5, 60, 7, 80]);
+        let b: uint16x8_t = transmute([10u16, 2, 30, 4, 50, 6, 70, 8]);
+        let max: [u16; 8] = transmute(vmaxq_u16(a, b));
+        assert_eq!(max, [10, 20, 30, 40, 50, 60, 70, 80]);
+
+        let a: int8x8_t = transmute([i8::MAX, i8::MIN, 1, 2, 3, 4, 5, 6]);
+        let b: int8x8_t = transmute([1i8, -1, 1, 1, 1, 1, 1, 1]);
+        let sum: [i8; 8] = transmute(vqadd_s8(a, b));
+        assert_eq!(sum, [i8::MAX, i8::MIN, 2, 3, 4, 5, 6, 7]);
+
+        let a: uint64x2_t = transmute([1u64, 10]);
+        let b: uint64x2_t = transmute([2u64, 3]);
+        let difference: [u64; 2] = transmute(vqsubq_u64(a, b));
+        assert_eq!(difference, [0, 7]);
+
+        let a: uint8x8_t = transmute([1u8, 2, 3, 4, 5, 6, 7, 255]);
+        let b: uint8x8_t = transmute([3u8, 2, 1, 4, 6, 6, 8, 255]);
+        let average: [u8; 8] = transmute(vhadd_u8(a, b));
+        assert_eq!(average, [2, 2, 2, 4, 5, 6, 7, 255]);
+
+        let a: int16x4_t = transmute([1i16, 2, 3, 4]);
+        let b: int16x4_t = transmute([10i16, 20, 30, 40]);
+        let sum: [i16; 4] = transmute(vpadd_s16(a, b));
+        assert_eq!(sum, [3, 7, 30, 70]);
+
+        let table: uint8x16_t = transmute([10u8, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25]);
+        let indices: uint8x8_t = transmute([15u8, 0, 1, 16, 2, 200, 3, 4]);
+        let lookup: [u8; 8] = transmute(vqtbl1_u8(table, indices));
+        assert_eq!(lookup, [25, 10, 11, 0, 12, 0, 13, 14]);
+    }
+}
+
+#[cfg(not(target_arch = "aarch64"))]
+fn check_neon() {
+}
+
+#[start]
+fn main(_argc: isize, _argv: *const *const u8) -> isize {
+    check_neon();
+    0
+}
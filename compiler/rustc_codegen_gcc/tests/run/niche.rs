@@ -0,0 +1,89 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, start)]
+
+#![no_std]
+
+use core::hint::black_box;
+use core::num::{NonZeroI32, NonZeroU32};
+
+#[panic_handler]
+fn panic_handler(_: &core::panic::PanicInfo) -> ! {
+    core::intrinsics::abort();
+}
+
+/*
+ * Code
+ */
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Niche {
+    Value(bool),
+    First,
+    Second,
+    Third,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Signed {
+    Value(NonZeroI32),
+    Empty,
+}
+
+#[inline(never)]
+fn classify(value: Niche) -> u8 {
+    match value {
+        Niche::Value(false) => 0,
+        Niche::Value(true) => 1,
+        Niche::First => 2,
+        Niche::Second => 3,
+        Niche::Third => 4,
+    }
+}
+
+#[inline(never)]
+fn set_last(value: &mut Option<Option<NonZeroU32>>) {
+    *value = Some(None);
+}
+
+#[start]
+fn main(_argc: isize, _argv: *const *const u8) -> isize {
+    let reference = 42;
+    let some_ref: Option<&i32> = black_box(Some(&reference));
+    let none_ref: Option<&i32> = black_box(None);
+    assert_eq!(some_ref.copied(), Some(42));
+    assert!(none_ref.is_none());
+
+    let nested: Option<Option<NonZeroU32>> = black_box(Some(NonZeroU32::new(7)));
+    assert_eq!(nested, Some(Some(NonZeroU32::new(7).unwrap())));
+    let mut nested = black_box(nested);
+    set_last(&mut nested);
+    assert_eq!(nested, Some(None));
+    assert_eq!(black_box(None::<Option<NonZeroU32>>), None);
+
+    let values = [Niche::Value(false), Niche::Value(true), Niche::First, Niche::Second, Niche::Third];
+    for (index, value) in values.iter().enumerate() {
+        assert_eq!(classify(black_box(*value)), index as u8);
+    }
+
+    let signed = [
+        Some(Signed::Value(NonZeroI32::new(i32::MIN).unwrap())),
+        Some(Signed::Value(NonZeroI32::new(-1).unwrap())),
+        Some(Signed::Empty),
+        None,
+    ];
+    let signed = black_box(signed);
+    assert!(matches!(signed[0], Some(Signed::Value(value)) if value.get() == i32::MIN));
+    assert!(matches!(signed[1], Some(Signed::Value(value)) if value.get() == -1));
+    assert_eq!(signed[2], Some(Signed::Empty));
+    assert_eq!(signed[3], None);
+
+    let chars: [Option<Option<char>>; 3] = black_box([Some(Some('a')), Some(None), None]);
+    assert_eq!(chars[0], Some(Some('a')));
+    assert_eq!(chars[1], Some(None));
+    assert_eq!(chars[2], None);
+    0
+}
@@ -0,0 +1,69 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, start)]
+
+#![no_std]
+
+use core::hint::black_box;
+
+#[panic_handler]
+fn panic_handler(_: &core::panic::PanicInfo) -> ! {
+    core::intrinsics::abort();
+}
+
+/*
+ * Code
+ */
+
+#[repr(packed)]
+#[derive(Clone, Copy)]
+struct Packed {
+    tag: u8,
+    value: u32,
+    wide: u64,
+}
+
+#[repr(C, packed(2))]
+#[derive(Clone, Copy)]
+struct Packed2 {
+    tag: u8,
+    value: u64,
+}
+
+#[inline(never)]
+fn sum(packed: &Packed) -> u64 {
+    packed.tag as u64 + packed.value as u64 + packed.wide
+}
+
+#[inline(never)]
+fn update(packed: &mut Packed, value: u32, wide: u64) {
+    packed.value = value;
+    packed.wide = wide;
+}
+
+#[start]
+fn main(_argc: isize, _argv: *const *const u8) -> isize {
+    let mut packed = black_box(Packed { tag: 1, value: 2, wide: 3 });
+    assert_eq!(sum(&packed), 6);
+    update(&mut packed, 0x1234_5678, 0x1_0000_0000);
+    let value = packed.value;
+    let wide = packed.wide;
+    assert_eq!(value, 0x1234_5678);
+    assert_eq!(wide, 0x1_0000_0000);
+
+    let mut array = black_box([Packed { tag: 0, value: 0, wide: 0 }; 3]);
+    for (i, packed) in array.iter_mut().enumerate() {
+        update(packed, i as u32, i as u64 * 2);
+    }
+    assert_eq!(array.iter().map(sum).sum::<u64>(), 9);
+
+    let mut packed2 = black_box(Packed2 { tag: 7, value: 8 });
+    packed2.value += 1;
+    let value = packed2.value;
+    assert_eq!(value, 9);
+    assert_eq!(packed2.tag, 7);
+    0
+}
@@ -0,0 +1,36 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, start)]
+
+#![no_std]
+
+use core::hint::black_box;
+use core::intrinsics::raw_eq;
+
+#[panic_handler]
+fn panic_handler(_: &core::panic::PanicInfo) -> ! {
+    core::intrinsics::abort();
+}
+
+/*
+ * Code
+ */
+
+#[start]
+fn main(_argc: isize, _argv: *const *const u8) -> isize {
+    unsafe {
+        assert!(raw_eq(&black_box([1u8, 2, 3, 4]), &[1, 2, 3, 4]));
+        assert!(!raw_eq(&black_box([1u8, 2, 3, 4]), &[1, 2, 3, 5]));
+        assert!(raw_eq(&black_box([1u16, 2]), &[1, 2]));
+        assert!(raw_eq(&black_box(0x1234_5678_u64), &0x1234_5678));
+        assert!(!raw_eq(&black_box(0x1234_5678_u64), &0x1234_5679));
+        assert!(raw_eq(&black_box([1i32, 2, 3]), &[1, 2, 3]));
+        assert!(!raw_eq(&black_box([1i32, 2, 3]), &[1, 2, 4]));
+        assert!(raw_eq(&black_box([7u64; 4]), &[7; 4]));
+        assert!(raw_eq(&black_box(()), &()));
+    }
+    0
+}
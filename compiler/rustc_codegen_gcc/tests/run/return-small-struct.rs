@@ -0,0 +1,75 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, start)]
+
+#![no_std]
+
+use core::hint::black_box;
+
+#[panic_handler]
+fn panic_handler(_: &core::panic::PanicInfo) -> ! {
+    core::intrinsics::abort();
+}
+
+/*
+ * Code
+ */
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct TwoWords {
+    a: u64,
+    b: u64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Mixed {
+    a: u32,
+    b: f32,
+    c: f64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct ThreeInts {
+    a: u32,
+    b: u32,
+    c: u32,
+}
+
+extern "C" fn two_words(a: u64, b: u64) -> TwoWords {
+    TwoWords { a, b }
+}
+
+extern "C" fn swap(value: TwoWords) -> TwoWords {
+    TwoWords { a: value.b, b: value.a }
+}
+
+extern "C" fn mixed(a: u32) -> Mixed {
+    Mixed { a, b: a as f32 / 2.0, c: a as f64 * 2.0 }
+}
+
+extern "C" fn three_ints(a: u32) -> ThreeInts {
+    ThreeInts { a, b: a + 1, c: a + 2 }
+}
+
+#[start]
+fn main(_argc: isize, _argv: *const *const u8) -> isize {
+    let two_words_fn: extern "C" fn(u64, u64) -> TwoWords = black_box(two_words);
+    let value = two_words_fn(black_box(1), black_box(u64::MAX));
+    assert_eq!(value, TwoWords { a: 1, b: u64::MAX });
+
+    let swap_fn: extern "C" fn(TwoWords) -> TwoWords = black_box(swap);
+    assert_eq!(swap_fn(value), TwoWords { a: u64::MAX, b: 1 });
+
+    let mixed_fn: extern "C" fn(u32) -> Mixed = black_box(mixed);
+    assert_eq!(mixed_fn(black_box(3)), Mixed { a: 3, b: 1.5, c: 6.0 });
+
+    let three_ints_fn: extern "C" fn(u32) -> ThreeInts = black_box(three_ints);
+    assert_eq!(three_ints_fn(black_box(7)), ThreeInts { a: 7, b: 8, c: 9 });
+    0
+}
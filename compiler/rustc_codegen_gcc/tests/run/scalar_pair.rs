@@ -0,0 +1,98 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, start)]
+
+#![no_std]
+
+use core::hint::black_box;
+
+#[panic_handler]
+fn panic_handler(_: &core::panic::PanicInfo) -> ! {
+    core::intrinsics::abort();
+}
+
+/*
+ * Code
+ */
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Pair {
+    a: u8,
+    b: u64,
+}
+
+#[inline(never)]
+fn make_pair(a: u8, b: u64) -> Pair {
+    Pair { a, b }
+}
+
+#[inline(never)]
+fn swap_pair(pair: Pair) -> (u64, u8) {
+    (pair.b, pair.a)
+}
+
+#[inline(never)]
+fn bump_second(mut pair: (u32, bool)) -> (u32, bool) {
+    pair.1 = !pair.1;
+    pair
+}
+
+#[inline(never)]
+fn slice_len(slice: &[u16]) -> usize {
+    slice.len()
+}
+
+#[inline(never)]
+fn tail(slice: &[u16]) -> &[u16] {
+    &slice[1..]
+}
+
+trait Value {
+    fn value(&self) -> u32;
+}
+
+impl Value for u32 {
+    fn value(&self) -> u32 {
+        *self
+    }
+}
+
+#[inline(never)]
+fn dyn_value(value: &dyn Value) -> u32 {
+    value.value()
+}
+
+#[inline(never)]
+fn checked(value: u32) -> Result<u32, u8> {
+    if value < 10 { Ok(value * 2) } else { Err(value as u8) }
+}
+
+#[start]
+fn main(_argc: isize, _argv: *const *const u8) -> isize {
+    let pair = make_pair(black_box(3), black_box(0x1_0000_0000));
+    assert_eq!(pair, Pair { a: 3, b: 0x1_0000_0000 });
+    assert_eq!(swap_pair(pair), (0x1_0000_0000, 3));
+
+    let mut pairs = [black_box(make_pair(1, 2)); 3];
+    pairs[1] = make_pair(4, 5);
+    assert_eq!(pairs[1].a, 4);
+    assert_eq!(pairs[2], Pair { a: 1, b: 2 });
+
+    assert_eq!(bump_second(black_box((7, false))), (7, true));
+    assert_eq!(bump_second(bump_second(black_box((8, true)))), (8, true));
+
+    let values = [1u16, 2, 3, 4];
+    let slice = black_box(&values[..]);
+    assert_eq!(slice_len(slice), 4);
+    assert_eq!(tail(tail(slice)), &[3, 4]);
+    assert_eq!(dyn_value(black_box(&42u32)), 42);
+
+    assert_eq!(checked(black_box(4)), Ok(8));
+    assert_eq!(checked(black_box(12)), Err(12));
+    let option: Option<&u32> = black_box(Some(&5));
+    assert_eq!(option.copied(), Some(5));
+    0
+}
@@ -0,0 +1,81 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, platform_intrinsics, repr_simd, start)]
+
+#![no_std]
+
+#[panic_handler]
+fn panic_handler(_: &core::panic::PanicInfo) -> ! {
+    core::intrinsics::abort();
+}
+
+/*
+ * Code
+ */
+
+#[repr(simd)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct i32x4(i32, i32, i32, i32);
+
+#[repr(simd)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct u32x4(u32, u32, u32, u32);
+
+#[repr(simd)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct f32x4(f32, f32, f32, f32);
+
+extern "platform-intrinsic" {
+    fn simd_add<T>(x: T, y: T) -> T;
+    fn simd_sub<T>(x: T, y: T) -> T;
+    fn simd_mul<T>(x: T, y: T) -> T;
+    fn simd_div<T>(x: T, y: T) -> T;
+    fn simd_rem<T>(x: T, y: T) -> T;
+    fn simd_shl<T>(x: T, y: T) -> T;
+    fn simd_shr<T>(x: T, y: T) -> T;
+    fn simd_and<T>(x: T, y: T) -> T;
+    fn simd_or<T>(x: T, y: T) -> T;
+    fn simd_xor<T>(x: T, y: T) -> T;
+    fn simd_neg<T>(x: T) -> T;
+}
+
+#[start]
+fn main(_argc: isize, _argv: *const *const u8) -> isize {
+    let a = i32x4(-8, 7, -6, 5);
+    let b = i32x4(2, 3, 4, 1);
+    unsafe {
+        assert_eq!(simd_add(a, b), i32x4(-6, 10, -2, 6));
+        assert_eq!(simd_sub(a, b), i32x4(-10, 4, -10, 4));
+        assert_eq!(simd_mul(a, b), i32x4(-16, 21, -24, 5));
+        assert_eq!(simd_div(a, b), i32x4(-4, 2, -1, 5));
+        assert_eq!(simd_rem(a, b), i32x4(0, 1, -2, 0));
+        assert_eq!(simd_shl(a, b), i32x4(-32, 56, -96, 10));
+        assert_eq!(simd_shr(a, b), i32x4(-2, 0, -1, 2));
+        assert_eq!(simd_and(a, b), i32x4(0, 3, 0, 1));
+        assert_eq!(simd_or(a, b), i32x4(-6, 7, -2, 5));
+        assert_eq!(simd_xor(a, b), i32x4(-6, 4, -2, 4));
+        assert_eq!(simd_neg(a), i32x4(8, -7, 6, -5));
+    }
+
+    let a = u32x4(u32::MAX, 7, 0x8000_0000, 5);
+    let b = u32x4(2, 3, 4, 1);
+    unsafe {
+        assert_eq!(simd_add(a, b), u32x4(1, 10, 0x8000_0004, 6));
+        assert_eq!(simd_div(a, b), u32x4(u32::MAX / 2, 2, 0x2000_0000, 5));
+        assert_eq!(simd_rem(a, b), u32x4(1, 1, 0, 0));
+        assert_eq!(simd_shr(a, b), u32x4(u32::MAX >> 2, 0, 0x0800_0000, 2));
+    }
+
+    let a = f32x4(1.5, -2.0, 3.0, 8.0);
+    let b = f32x4(0.5, 4.0, -1.0, 2.0);
+    unsafe {
+        assert_eq!(simd_add(a, b), f32x4(2.0, 2.0, 2.0, 10.0));
+        assert_eq!(simd_mul(a, b), f32x4(0.75, -8.0, -3.0, 16.0));
+        assert_eq!(simd_div(a, b), f32x4(3.0, -0.5, -3.0, 4.0));
+        assert_eq!(simd_neg(a), f32x4(-1.5, 2.0, -3.0, -8.0));
+    }
+    0
+}
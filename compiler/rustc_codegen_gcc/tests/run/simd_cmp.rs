@@ -0,0 +1,82 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, platform_intrinsics, repr_simd, start)]
+
+#![no_std]
+
+#[panic_handler]
+fn panic_handler(_: &core::panic::PanicInfo) -> ! {
+    core::intrinsics::abort();
+}
+
+/*
+ * Code
+ */
+
+#[repr(simd)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct i32x4(i32, i32, i32, i32);
+
+#[repr(simd)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct u32x4(u32, u32, u32, u32);
+
+#[repr(simd)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct i64x4(i64, i64, i64, i64);
+
+#[repr(simd)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct i8x4(i8, i8, i8, i8);
+
+#[repr(simd)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct f32x4(f32, f32, f32, f32);
+
+extern "platform-intrinsic" {
+    fn simd_eq<T, U>(x: T, y: T) -> U;
+    fn simd_ne<T, U>(x: T, y: T) -> U;
+    fn simd_lt<T, U>(x: T, y: T) -> U;
+    fn simd_le<T, U>(x: T, y: T) -> U;
+    fn simd_gt<T, U>(x: T, y: T) -> U;
+    fn simd_ge<T, U>(x: T, y: T) -> U;
+}
+
+#[start]
+fn main(_argc: isize, _argv: *const *const u8) -> isize {
+    let a = i32x4(-1, 2, 3, 4);
+    let b = i32x4(1, 2, -3, 5);
+    unsafe {
+        assert_eq!(simd_eq::<_, i32x4>(a, b), i32x4(0, -1, 0, 0));
+        assert_eq!(simd_ne::<_, i32x4>(a, b), i32x4(-1, 0, -1, -1));
+        assert_eq!(simd_lt::<_, i32x4>(a, b), i32x4(-1, 0, 0, -1));
+        assert_eq!(simd_le::<_, i32x4>(a, b), i32x4(-1, -1, 0, -1));
+        assert_eq!(simd_gt::<_, i32x4>(a, b), i32x4(0, 0, -1, 0));
+        assert_eq!(simd_ge::<_, i32x4>(a, b), i32x4(0, -1, -1, 0));
+
+        // Masks of a different element size.
+        assert_eq!(simd_lt::<_, i64x4>(a, b), i64x4(-1, 0, 0, -1));
+        assert_eq!(simd_lt::<_, i8x4>(a, b), i8x4(-1, 0, 0, -1));
+    }
+
+    // Unsigned comparisons.
+    let a = u32x4(u32::MAX, 2, 3, 4);
+    let b = u32x4(1, 2, 0x8000_0000, 5);
+    unsafe {
+        assert_eq!(simd_lt::<_, i32x4>(a, b), i32x4(0, 0, -1, -1));
+        assert_eq!(simd_ge::<_, i32x4>(a, b), i32x4(-1, -1, 0, 0));
+    }
+
+    let a = f32x4(-1.0, 2.0, f32::NAN, 4.0);
+    let b = f32x4(1.0, 2.0, 3.0, -5.0);
+    unsafe {
+        assert_eq!(simd_eq::<_, i32x4>(a, b), i32x4(0, -1, 0, 0));
+        assert_eq!(simd_ne::<_, i32x4>(a, b), i32x4(-1, 0, -1, -1));
+        assert_eq!(simd_lt::<_, i32x4>(a, b), i32x4(-1, 0, 0, 0));
+        assert_eq!(simd_ge::<_, i64x4>(a, b), i64x4(0, -1, 0, -1));
+    }
+    0
+}
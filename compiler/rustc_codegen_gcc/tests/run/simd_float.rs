@@ -0,0 +1,65 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, platform_intrinsics, repr_simd, start)]
+
+#![no_std]
+
+#[panic_handler]
+fn panic_handler(_: &core::panic::PanicInfo) -> ! {
+    core::intrinsics::abort();
+}
+
+/*
+ * Code
+ */
+
+#[repr(simd)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct f32x4(f32, f32, f32, f32);
+
+#[repr(simd)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct f64x2(f64, f64);
+
+extern "platform-intrinsic" {
+    fn simd_fabs<T>(x: T) -> T;
+    fn simd_fsqrt<T>(x: T) -> T;
+    fn simd_fma<T>(x: T, y: T, z: T) -> T;
+    fn simd_fmin<T>(x: T, y: T) -> T;
+    fn simd_fmax<T>(x: T, y: T) -> T;
+    fn simd_floor<T>(x: T) -> T;
+    fn simd_ceil<T>(x: T) -> T;
+    fn simd_round<T>(x: T) -> T;
+    fn simd_trunc<T>(x: T) -> T;
+    fn simd_fpowi<T>(x: T, y: i32) -> T;
+}
+
+#[start]
+fn main(_argc: isize, _argv: *const *const u8) -> isize {
+    let a = f32x4(-1.5, 4.0, -2.5, 9.0);
+    let b = f32x4(2.0, f32::NAN, -3.0, 1.0);
+    unsafe {
+        assert_eq!(simd_fabs(a), f32x4(1.5, 4.0, 2.5, 9.0));
+        assert_eq!(simd_fsqrt(f32x4(1.0, 4.0, 16.0, 9.0)), f32x4(1.0, 2.0, 4.0, 3.0));
+        assert_eq!(simd_fma(a, f32x4(2.0, 2.0, 2.0, 2.0), f32x4(1.0, 1.0, 1.0, 1.0)), f32x4(-2.0, 9.0, -4.0, 19.0));
+        // NOTE: the non-NaN operand is returned.
+        assert_eq!(simd_fmin(a, b), f32x4(-1.5, 4.0, -3.0, 1.0));
+        assert_eq!(simd_fmax(a, b), f32x4(2.0, 4.0, -2.5, 9.0));
+        assert_eq!(simd_floor(a), f32x4(-2.0, 4.0, -3.0, 9.0));
+        assert_eq!(simd_ceil(a), f32x4(-1.0, 4.0, -2.0, 9.0));
+        assert_eq!(simd_round(a), f32x4(-2.0, 4.0, -3.0, 9.0));
+        assert_eq!(simd_trunc(a), f32x4(-1.0, 4.0, -2.0, 9.0));
+        assert_eq!(simd_fpowi(a, 2), f32x4(2.25, 16.0, 6.25, 81.0));
+    }
+
+    let a = f64x2(-0.25, 16.0);
+    unsafe {
+        assert_eq!(simd_fabs(a), f64x2(0.25, 16.0));
+        assert_eq!(simd_fsqrt(simd_fabs(a)), f64x2(0.5, 4.0));
+        assert_eq!(simd_fmax(a, f64x2(f64::NAN, 20.0)), f64x2(-0.25, 20.0));
+    }
+    0
+}
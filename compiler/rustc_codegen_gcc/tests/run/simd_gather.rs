@@ -0,0 +1,65 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, platform_intrinsics, repr_simd, start)]
+
+#![no_std]
+
+#[panic_handler]
+fn panic_handler(_: &core::panic::PanicInfo) -> ! {
+    core::intrinsics::abort();
+}
+
+/*
+ * Code
+ */
+
+#[repr(simd)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct f32x4(f32, f32, f32, f32);
+
+#[repr(simd)]
+#[derive(Clone, Copy)]
+struct ptrx4<T>(T, T, T, T);
+
+#[repr(simd)]
+#[derive(Clone, Copy)]
+struct i32x4(i32, i32, i32, i32);
+
+extern "platform-intrinsic" {
+    fn simd_gather<T, U, V>(values: T, pointers: U, mask: V) -> T;
+    fn simd_scatter<T, U, V>(values: T, pointers: U, mask: V);
+}
+
+#[start]
+fn main(_argc: isize, _argv: *const *const u8) -> isize {
+    let mut array = [10.0_f32, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0, 17.0];
+    let default = f32x4(-1.0, -2.0, -3.0, -4.0);
+    let mask = i32x4(-1, 0, -1, -1);
+
+    let pointer = array.as_ptr();
+    let pointers = ptrx4(
+        pointer.wrapping_add(7),
+        // NOTE: masked-off lanes must not be dereferenced.
+        core::ptr::null(),
+        pointer.wrapping_add(2),
+        pointer.wrapping_add(0),
+    );
+    let values: f32x4 = unsafe { simd_gather(default, pointers, mask) };
+    assert_eq!(values, f32x4(17.0, -2.0, 12.0, 10.0));
+
+    let pointer = array.as_mut_ptr();
+    let pointers = ptrx4(
+        pointer.wrapping_add(1),
+        core::ptr::null_mut(),
+        pointer.wrapping_add(5),
+        pointer.wrapping_add(6),
+    );
+    unsafe {
+        simd_scatter(f32x4(1.0, 2.0, 3.0, 4.0), pointers, mask);
+    }
+    assert_eq!(array, [10.0, 1.0, 12.0, 13.0, 14.0, 3.0, 4.0, 17.0]);
+    0
+}
@@ -0,0 +1,54 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, platform_intrinsics, repr_simd, start)]
+
+#![no_std]
+
+#[panic_handler]
+fn panic_handler(_: &core::panic::PanicInfo) -> ! {
+    core::intrinsics::abort();
+}
+
+/*
+ * Code
+ */
+
+#[repr(simd)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct i32x4(i32, i32, i32, i32);
+
+#[repr(simd)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct i8x4(i8, i8, i8, i8);
+
+#[repr(simd)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct f64x4(f64, f64, f64, f64);
+
+extern "platform-intrinsic" {
+    fn simd_select<M, T>(mask: M, a: T, b: T) -> T;
+    fn simd_select_bitmask<M, T>(mask: M, a: T, b: T) -> T;
+}
+
+#[start]
+fn main(_argc: isize, _argv: *const *const u8) -> isize {
+    let a = i32x4(1, 2, 3, 4);
+    let b = i32x4(-5, -6, -7, -8);
+    unsafe {
+        assert_eq!(simd_select(i32x4(-1, 0, 0, -1), a, b), i32x4(1, -6, -7, 4));
+        // Mask of a different element size.
+        assert_eq!(simd_select(i8x4(0, -1, 0, -1), a, b), i32x4(-5, 2, -7, 4));
+        assert_eq!(simd_select_bitmask(0b0101_u8, a, b), i32x4(1, -6, 3, -8));
+    }
+
+    let a = f64x4(1.5, 2.5, 3.5, 4.5);
+    let b = f64x4(-1.0, -2.0, -3.0, -4.0);
+    unsafe {
+        assert_eq!(simd_select(i32x4(0, -1, -1, 0), a, b), f64x4(-1.0, 2.5, 3.5, -4.0));
+        assert_eq!(simd_select_bitmask(0b1100_u8, a, b), f64x4(-1.0, -2.0, 3.5, 4.5));
+    }
+    0
+}
@@ -0,0 +1,68 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, platform_intrinsics, repr_simd, start)]
+
+#![no_std]
+
+#[panic_handler]
+fn panic_handler(_: &core::panic::PanicInfo) -> ! {
+    core::intrinsics::abort();
+}
+
+/*
+ * Code
+ */
+
+#[repr(simd)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct u32x4(u32, u32, u32, u32);
+
+#[repr(simd)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct u32x2(u32, u32);
+
+#[repr(simd)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct u32x8(u32, u32, u32, u32, u32, u32, u32, u32);
+
+#[repr(simd)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct f32x4(f32, f32, f32, f32);
+
+extern "platform-intrinsic" {
+    fn simd_shuffle<T, I, U>(a: T, b: T, index: I) -> U;
+    fn simd_shuffle2<T, U>(a: T, b: T, index: [u32; 2]) -> U;
+    fn simd_shuffle4<T, U>(a: T, b: T, index: [u32; 4]) -> U;
+    fn simd_shuffle8<T, U>(a: T, b: T, index: [u32; 8]) -> U;
+}
+
+#[start]
+fn main(_argc: isize, _argv: *const *const u8) -> isize {
+    let a = u32x4(0, 1, 2, 3);
+    let b = u32x4(4, 5, 6, 7);
+
+    // Same length as the inputs.
+    let res: u32x4 = unsafe { simd_shuffle4(a, b, [3, 4, 1, 6]) };
+    assert_eq!(res, u32x4(3, 4, 1, 6));
+
+    const INDEX: [u32; 4] = [7, 0, 5, 2];
+    let res: u32x4 = unsafe { simd_shuffle(a, b, INDEX) };
+    assert_eq!(res, u32x4(7, 0, 5, 2));
+
+    // Shorter than the inputs.
+    let res: u32x2 = unsafe { simd_shuffle2(a, b, [6, 1]) };
+    assert_eq!(res, u32x2(6, 1));
+
+    // Longer than the inputs.
+    let res: u32x8 = unsafe { simd_shuffle8(a, b, [7, 6, 5, 4, 3, 2, 1, 0]) };
+    assert_eq!(res, u32x8(7, 6, 5, 4, 3, 2, 1, 0));
+
+    let a = f32x4(0.5, 1.5, 2.5, 3.5);
+    let b = f32x4(4.5, 5.5, 6.5, 7.5);
+    let res: f32x4 = unsafe { simd_shuffle4(a, b, [0, 4, 1, 5]) };
+    assert_eq!(res, f32x4(0.5, 4.5, 1.5, 5.5));
+    0
+}
@@ -0,0 +1,51 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, platform_intrinsics, repr_simd, start)]
+
+#![no_std]
+
+#[panic_handler]
+fn panic_handler(_: &core::panic::PanicInfo) -> ! {
+    core::intrinsics::abort();
+}
+
+/*
+ * Code
+ */
+
+#[repr(simd)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct u8x4(u8, u8, u8, u8);
+
+#[repr(simd)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct u8x16(u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8);
+
+extern "platform-intrinsic" {
+    fn simd_swizzle_dyn<T>(values: T, indices: T) -> T;
+}
+
+#[start]
+fn main(_argc: isize, _argv: *const *const u8) -> isize {
+    // Scalar fallback.
+    let values = u8x4(10, 11, 12, 13);
+    let res: u8x4 = unsafe { simd_swizzle_dyn(values, u8x4(3, 2, 1, 0)) };
+    assert_eq!(res, u8x4(13, 12, 11, 10));
+    let res: u8x4 = unsafe { simd_swizzle_dyn(values, u8x4(0, 4, 255, 1)) };
+    assert_eq!(res, u8x4(10, 0, 0, 11));
+
+    // pshufb on x86-64 and tbl on AArch64.
+    let values = u8x16(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15);
+    let indices = u8x16(15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0);
+    let res: u8x16 = unsafe { simd_swizzle_dyn(values, indices) };
+    assert_eq!(res, indices);
+
+    // The out-of-range indices include ones that have the same low bits as an in-range index.
+    let indices = u8x16(0, 16, 17, 31, 32, 128, 143, 255, 1, 2, 3, 4, 5, 6, 7, 8);
+    let res: u8x16 = unsafe { simd_swizzle_dyn(values, indices) };
+    assert_eq!(res, u8x16(0, 0, 0, 0, 0, 0, 0, 0, 1, 2, 3, 4, 5, 6, 7, 8));
+    0
+}
@@ -0,0 +1,78 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, start)]
+
+#![no_std]
+
+use core::mem::transmute;
+
+#[panic_handler]
+fn panic_handler(_: &core::panic::PanicInfo) -> ! {
+    core::intrinsics::abort();
+}
+
+/*
+ * Code
+ */
+
+#[inline(never)]
+fn f32_to_bits(x: f32) -> u32 {
+    unsafe { transmute(x) }
+}
+
+#[inline(never)]
+fn f64_from_bits(bits: u64) -> f64 {
+    unsafe { transmute(bits) }
+}
+
+#[inline(never)]
+fn ptr_to_int(ptr: *const u8) -> usize {
+    unsafe { transmute(ptr) }
+}
+
+#[inline(never)]
+fn int_to_ptr(int: usize) -> *const u8 {
+    unsafe { transmute(int) }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn check_vectors() {
+    use core::arch::x86_64::{__m128i, _mm_set_epi32};
+
+    #[inline(never)]
+    fn vector_to_int(vector: __m128i) -> u128 {
+        unsafe { transmute(vector) }
+    }
+
+    #[inline(never)]
+    fn vector_to_array(vector: __m128i) -> [u32; 4] {
+        unsafe { transmute(vector) }
+    }
+
+    let vector = unsafe { _mm_set_epi32(4, 3, 2, 1) };
+    assert_eq!(vector_to_int(vector), 0x4_0000_0003_0000_0002_0000_0001);
+    assert_eq!(vector_to_array(vector), [1, 2, 3, 4]);
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn check_vectors() {
+}
+
+#[start]
+fn main(_argc: isize, _argv: *const *const u8) -> isize {
+    assert_eq!(f32_to_bits(1.0), 0x3f80_0000);
+    assert_eq!(1.5f32.to_bits(), 0x3fc0_0000);
+    assert_eq!(f64_from_bits(0x4000_0000_0000_0000), 2.0);
+    assert_eq!(f32::from_bits(0x4040_0000), 3.0);
+
+    let byte = 42u8;
+    let ptr = &byte as *const u8;
+    assert_eq!(int_to_ptr(ptr_to_int(ptr)), ptr);
+    assert_eq!(unsafe { *int_to_ptr(ptr_to_int(ptr)) }, 42);
+
+    check_vectors();
+    0
+}
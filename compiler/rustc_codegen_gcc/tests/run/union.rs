@@ -0,0 +1,102 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, start)]
+
+#![no_std]
+
+use core::hint::black_box;
+use core::mem::ManuallyDrop;
+
+#[panic_handler]
+fn panic_handler(_: &core::panic::PanicInfo) -> ! {
+    core::intrinsics::abort();
+}
+
+/*
+ * Code
+ */
+
+#[derive(Clone, Copy)]
+union IntOrFloat {
+    int: u32,
+    float: f32,
+}
+
+#[derive(Clone, Copy)]
+union SmallOrLarge {
+    small: u8,
+    large: u64,
+    pair: (u16, u16),
+}
+
+#[derive(Clone, Copy)]
+#[repr(C)]
+union Bytes {
+    value: u32,
+    bytes: [u8; 4],
+}
+
+static mut DROPS: u32 = 0;
+
+struct Named(&'static str);
+
+impl Drop for Named {
+    fn drop(&mut self) {
+        unsafe {
+            DROPS += 1;
+        }
+    }
+}
+
+union WithDrop {
+    value: ManuallyDrop<Named>,
+    len: usize,
+}
+
+static STATIC_UNION: IntOrFloat = IntOrFloat { float: 1.0 };
+
+#[inline(never)]
+fn float_bits(value: IntOrFloat) -> u32 {
+    unsafe { value.int }
+}
+
+#[inline(never)]
+fn make_large(value: u64) -> SmallOrLarge {
+    SmallOrLarge { large: value }
+}
+
+#[inline(never)]
+fn first_byte(value: Bytes) -> u8 {
+    unsafe { value.bytes[0] }
+}
+
+#[start]
+fn main(_argc: isize, _argv: *const *const u8) -> isize {
+    assert_eq!(float_bits(black_box(IntOrFloat { float: 1.0 })), 0x3f80_0000);
+    assert_eq!(unsafe { STATIC_UNION.int }, 0x3f80_0000);
+
+    let mut value = black_box(IntOrFloat { int: 0 });
+    value.float = 2.0;
+    assert_eq!(unsafe { value.int }, 0x4000_0000);
+
+    let large = make_large(black_box(0x1122_3344_5566_7788));
+    assert_eq!(unsafe { large.small }, 0x88);
+    assert_eq!(unsafe { large.pair }, (0x7788, 0x5566));
+
+    let mut small = black_box(make_large(0));
+    small.small = 0xff;
+    assert_eq!(unsafe { small.large }, 0xff);
+
+    assert_eq!(first_byte(black_box(Bytes { value: 0x0403_0201 })), 1);
+
+    let mut with_drop = WithDrop { value: ManuallyDrop::new(Named("union")) };
+    assert_eq!(unsafe { with_drop.value.0.len() }, 5);
+    unsafe { ManuallyDrop::drop(&mut with_drop.value) };
+    assert_eq!(unsafe { DROPS }, 1);
+    with_drop.len = 3;
+    assert_eq!(unsafe { with_drop.len }, 3);
+    0
+}
@@ -0,0 +1,80 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(alloc_error_handler, core_intrinsics, start, unsized_fn_params, unsized_locals)]
+#![allow(incomplete_features)]
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::alloc::{GlobalAlloc, Layout};
+use alloc::boxed::Box;
+use core::hint::black_box;
+
+extern "C" {
+    fn malloc(size: usize) -> *mut u8;
+    fn free(ptr: *mut u8);
+}
+
+struct Malloc;
+
+unsafe impl GlobalAlloc for Malloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        malloc(layout.size())
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, _layout: Layout) {
+        free(ptr)
+    }
+}
+
+#[global_allocator]
+static ALLOC: Malloc = Malloc;
+
+#[panic_handler]
+fn panic_handler(_: &core::panic::PanicInfo) -> ! {
+    core::intrinsics::abort();
+}
+
+#[alloc_error_handler]
+fn alloc_error_handler(_: Layout) -> ! {
+    core::intrinsics::abort();
+}
+
+/*
+ * Code
+ */
+
+fn sum(values: [u32]) -> u32 {
+    values.iter().sum()
+}
+
+fn length(value: str) -> usize {
+    value.len()
+}
+
+fn call(function: Box<dyn FnOnce(u32) -> u32>, value: u32) -> u32 {
+    function(value)
+}
+
+#[start]
+fn main(_argc: isize, _argv: *const *const u8) -> isize {
+    let values: Box<[u32]> = black_box(Box::new([1, 2, 3, 4]));
+    assert_eq!(sum(*values), 10);
+
+    let string: Box<str> = black_box(Box::from("unsized"));
+    assert_eq!(length(*string), 7);
+
+    let local: Box<[u8]> = black_box(Box::new([5u8; 33]));
+    let local: [u8] = *local;
+    assert_eq!(local.len(), 33);
+    assert!(local.iter().all(|&value| value == 5));
+
+    let offset = black_box(40);
+    let captured = black_box([1u32, 2]);
+    assert_eq!(call(Box::new(move |value| value + offset + captured.len() as u32), 0), 42);
+    0
+}
@@ -0,0 +1,50 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, start)]
+
+#![no_std]
+
+use core::intrinsics::{unaligned_volatile_load, unaligned_volatile_store};
+use core::ptr::{read_volatile, write_volatile};
+
+#[panic_handler]
+fn panic_handler(_: &core::panic::PanicInfo) -> ! {
+    core::intrinsics::abort();
+}
+
+/*
+ * Code
+ */
+
+#[repr(C, packed)]
+struct Packed {
+    tag: u8,
+    value: u32,
+}
+
+#[start]
+fn main(_argc: isize, _argv: *const *const u8) -> isize {
+    let mut value = 42_u64;
+    unsafe {
+        assert_eq!(read_volatile(&value), 42);
+        write_volatile(&mut value, 1337);
+        assert_eq!(read_volatile(&value), 1337);
+    }
+    assert_eq!(value, 1337);
+
+    let mut packed = Packed { tag: 1, value: 0x1234_5678 };
+    unsafe {
+        let ptr = core::ptr::addr_of_mut!(packed.value);
+        assert_eq!(unaligned_volatile_load(ptr), 0x1234_5678);
+        unaligned_volatile_store(ptr, 0x8765_4321);
+        assert_eq!(unaligned_volatile_load(ptr), 0x8765_4321);
+        assert_eq!(ptr.read_unaligned(), 0x8765_4321);
+        ptr.write_unaligned(7);
+    }
+    assert_eq!({ packed.value }, 7);
+    assert_eq!(packed.tag, 1);
+    0
+}
@@ -0,0 +1,57 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, start)]
+
+#![no_std]
+
+#[panic_handler]
+fn panic_handler(_: &core::panic::PanicInfo) -> ! {
+    core::intrinsics::abort();
+}
+
+/*
+ * Code
+ */
+
+#[cfg(target_arch = "x86_64")]
+fn check_intrinsics() {
+    use core::arch::x86_64::{
+        __m128i, _addcarry_u32, _addcarry_u64, _mm_cmpeq_epi8, _mm_loadu_si128, _mm_movemask_epi8,
+        _mm_set1_epi8, _subborrow_u64,
+    };
+
+    let mut out = 0;
+    let carry = unsafe { _addcarry_u64(0, u64::MAX, 2, &mut out) };
+    assert_eq!((carry, out), (1, 1));
+    let carry = unsafe { _addcarry_u64(carry, 40, 1, &mut out) };
+    assert_eq!((carry, out), (0, 42));
+
+    let mut out = 0;
+    let carry = unsafe { _addcarry_u32(1, u32::MAX, 0, &mut out) };
+    assert_eq!((carry, out), (1, 0));
+
+    let mut out = 0;
+    let borrow = unsafe { _subborrow_u64(0, 1, 2, &mut out) };
+    assert_eq!((borrow, out), (1, u64::MAX));
+
+    // The intrinsics used by memchr.
+    let haystack = *b"hello, world!!!!";
+    let mask = unsafe {
+        let haystack = _mm_loadu_si128(haystack.as_ptr() as *const __m128i);
+        _mm_movemask_epi8(_mm_cmpeq_epi8(haystack, _mm_set1_epi8(b'o' as i8)))
+    };
+    assert_eq!(mask, 0b1_0001_0000);
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn check_intrinsics() {
+}
+
+#[start]
+fn main(_argc: isize, _argv: *const *const u8) -> isize {
+    check_intrinsics();
+    0
+}
@@ -0,0 +1,77 @@
+// Compiler:
+//
+// Run-time:
+//   status: 0
+
+#![feature(core_intrinsics, start)]
+
+#![no_std]
+
+use core::hint::black_box;
+use core::marker::PhantomData;
+
+#[panic_handler]
+fn panic_handler(_: &core::panic::PanicInfo) -> ! {
+    core::intrinsics::abort();
+}
+
+/*
+ * Code
+ */
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Empty;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Wrapper<T> {
+    before: Empty,
+    value: T,
+    marker: PhantomData<T>,
+    after: [u64; 0],
+}
+
+#[inline(never)]
+fn take_empty(_a: Empty, value: u32, _b: (), _c: [u8; 0]) -> u32 {
+    value + 1
+}
+
+#[inline(never)]
+fn make_wrapper(value: u16) -> Wrapper<u16> {
+    Wrapper { before: Empty, value, marker: PhantomData, after: [] }
+}
+
+#[inline(never)]
+fn return_empty(value: &mut u32) -> Empty {
+    *value += 1;
+    Empty
+}
+
+extern "C" fn c_empty(value: u32, _empty: Empty) -> u32 {
+    value * 2
+}
+
+#[start]
+fn main(_argc: isize, _argv: *const *const u8) -> isize {
+    assert_eq!(core::mem::size_of::<Wrapper<u16>>(), 8);
+    assert_eq!(take_empty(Empty, black_box(41), (), []), 42);
+
+    let wrapper = make_wrapper(black_box(7));
+    assert_eq!(wrapper.value, 7);
+    assert_eq!(wrapper.before, Empty);
+
+    let mut counter = 0;
+    let empties = [return_empty(&mut counter), return_empty(&mut counter)];
+    assert_eq!(counter, 2);
+    assert_eq!(empties.len(), 2);
+
+    let units: [(); 1000] = black_box([(); 1000]);
+    assert_eq!(units.len(), 1000);
+    assert_eq!(units.iter().count(), 1000);
+
+    let c_fn: extern "C" fn(u32, Empty) -> u32 = black_box(c_empty);
+    assert_eq!(c_fn(black_box(21), Empty), 42);
+
+    let empty_ref = black_box(&Empty);
+    assert_eq!(*empty_ref, Empty);
+    0
+}
@@ -1043,6 +1043,31 @@ macro_rules! require_simd {
             args[1].immediate(),
         ));
     }
+    if name == sym::simd_swizzle_dyn {
+        require!(
+            matches!(in_elem.kind(), ty::Uint(ty::UintTy::U8)),
+            "expected element type `u8` of input `{}`, found `{}`",
+            in_ty,
+            in_elem
+        );
+        let values = args[0].immediate();
+        let indices = args[1].immediate();
+        let len = bx.const_u8(in_len as u8);
+        let zero = bx.const_u8(0);
+        // Out-of-range indices select 0.
+        let mut result = bx.const_undef(llret_ty);
+        for i in 0..in_len {
+            let i = bx.const_i32(i as i32);
+            let index = bx.extract_element(indices, i);
+            let in_range = bx.icmp(IntPredicate::IntULT, index, len);
+            let index = bx.select(in_range, index, zero);
+            let value = bx.extract_element(values, index);
+            let value = bx.select(in_range, value, zero);
+            result = bx.insert_element(result, value, i);
+        }
+        return Ok(result);
+    }
+
     if name == sym::simd_extract {
         require!(
             ret_ty == in_elem,
@@ -7,9 +7,36 @@ codegen_gcc_linkage_const_or_mut_type =
 codegen_gcc_unwinding_inline_asm =
     GCC backend does not support unwinding from inline asm
 
+codegen_gcc_wasm_inline_asm =
+    GCC backend does not support inline asm on wasm targets
+
+codegen_gcc_unsupported_asm_register_class =
+    GCC backend does not support the `{$reg_class}` register class in inline asm
+
+codegen_gcc_unsupported_asm_clobber =
+    GCC backend ignores the clobber of the {$reg} register since the target does not support it
+
+codegen_gcc_c_variadic_not_supported =
+    GCC backend does not support defining C-variadic functions
+    .note = libgccjit provides no way to access the `va_list` of a function
+
+codegen_gcc_c_variadic_intrinsic_not_supported =
+    GCC backend does not support the `{$name}` intrinsic
+    .note = libgccjit provides no way to access the `va_list` of a function
+
+codegen_gcc_calling_convention_not_supported =
+    GCC backend does not support the `{$conv}` calling convention
+    .note = libgccjit provides no way to set the calling convention of a function
+
+codegen_gcc_invalid_minimum_alignment =
+    invalid minimum global alignment: {$err}
+
 codegen_gcc_lto_not_supported =
     LTO is not supported. You may get a linker error.
 
+codegen_gcc_split_debuginfo_not_supported =
+    split debuginfo is not supported: the debuginfo is kept in the object files
+
 codegen_gcc_invalid_monomorphization_basic_integer =
     invalid monomorphization of `{$name}` intrinsic: expected basic integer type, found `{$ty}`
 
@@ -66,3 +93,12 @@ codegen_gcc_invalid_monomorphization_unsupported_cast =
 
 codegen_gcc_invalid_monomorphization_unsupported_operation =
     invalid monomorphization of `{$name}` intrinsic: unsupported operation on `{$in_ty}` with element `{$in_elem}`
+
+codegen_gcc_invalid_monomorphization_expected_pointer_vector =
+    invalid monomorphization of `{$name}` intrinsic: expected `{$vec_ty}` to be a vector of `*{$mutability} {$in_elem}`
+
+codegen_gcc_dump_reproducer_failed =
+    failed to create the reproducer directory `{$path}`: {$error}
+
+codegen_gcc_non_utf8_path =
+    GCC backend cannot write to `{$path}` since the path is not valid UTF-8
@@ -1395,6 +1395,7 @@
         simd_shr,
         simd_shuffle,
         simd_sub,
+        simd_swizzle_dyn,
         simd_trunc,
         simd_xor,
         since,
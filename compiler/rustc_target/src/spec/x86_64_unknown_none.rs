@@ -5,7 +5,7 @@
 // features.
 
 use super::{CodeModel, LinkerFlavor, LldFlavor, PanicStrategy};
-use super::{RelroLevel, StackProbeType, Target, TargetOptions};
+use super::{RelroLevel, SanitizerSet, StackProbeType, Target, TargetOptions};
 
 pub fn target() -> Target {
     let opts = TargetOptions {
@@ -24,6 +24,7 @@ pub fn target() -> Target {
         disable_redzone: true,
         panic_strategy: PanicStrategy::Abort,
         code_model: Some(CodeModel::Kernel),
+        supported_sanitizers: SanitizerSet::KERNELADDRESS,
         ..Default::default()
     };
     Target {
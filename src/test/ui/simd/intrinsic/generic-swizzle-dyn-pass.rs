@@ -0,0 +1,52 @@
+// run-pass
+#![allow(non_camel_case_types)]
+
+// ignore-emscripten
+
+// Test that the simd_swizzle_dyn intrinsic produces correct results.
+
+#![feature(repr_simd, platform_intrinsics)]
+
+#[repr(simd)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct u8x4(pub u8, pub u8, pub u8, pub u8);
+
+#[repr(simd)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct u8x16(
+    pub u8, pub u8, pub u8, pub u8, pub u8, pub u8, pub u8, pub u8,
+    pub u8, pub u8, pub u8, pub u8, pub u8, pub u8, pub u8, pub u8,
+);
+
+extern "platform-intrinsic" {
+    fn simd_swizzle_dyn<T>(values: T, idxs: T) -> T;
+}
+
+fn main() {
+    let values = u8x4(10, 11, 12, 13);
+    unsafe {
+        let r: u8x4 = simd_swizzle_dyn(values, u8x4(3, 2, 1, 0));
+        assert_eq!(r, u8x4(13, 12, 11, 10));
+
+        // Out-of-range indices select 0.
+        let r: u8x4 = simd_swizzle_dyn(values, u8x4(0, 4, 255, 1));
+        assert_eq!(r, u8x4(10, 0, 0, 11));
+    }
+
+    let values = u8x16(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15);
+    unsafe {
+        let r: u8x16 = simd_swizzle_dyn(
+            values,
+            u8x16(15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0),
+        );
+        assert_eq!(r, u8x16(15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0));
+
+        // Out-of-range indices select 0, including the ones that only differ from an in-range
+        // index in their high bits.
+        let r: u8x16 = simd_swizzle_dyn(
+            values,
+            u8x16(0, 16, 17, 31, 32, 128, 143, 255, 1, 2, 3, 4, 5, 6, 7, 8),
+        );
+        assert_eq!(r, u8x16(0, 0, 0, 0, 0, 0, 0, 0, 1, 2, 3, 4, 5, 6, 7, 8));
+    }
+}